@@ -0,0 +1,385 @@
+use crate::grid::{self, Grid, Position};
+use std::{collections::HashSet, str::FromStr};
+
+const INPUT: &str = include_str!("day10.txt");
+
+/// Tile of the starting position.
+const START: char = 'S';
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid grid.
+    Grid(grid::InvalidInput),
+    /// The amount of starting tiles is not 1.
+    StartCount(usize),
+    /// The amount of pipes connected to the starting tile is not 2.
+    StartConnections(usize),
+    /// The loop from the starting tile reaches a tile which does not connect
+    ///   back to the previous one, or leaves the grid.
+    BrokenLoop(Position),
+}
+
+impl From<grid::InvalidInput> for InvalidInput {
+    fn from(error: grid::InvalidInput) -> Self {
+        Self::Grid(error)
+    }
+}
+
+/// A direction a pipe can lead to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Up.
+    North,
+    /// Right.
+    East,
+    /// Down.
+    South,
+    /// Left.
+    West,
+}
+
+impl Direction {
+    /// Every direction, clockwise.
+    const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    /// Get the offset in rows and columns.
+    const fn offset(self) -> (isize, isize) {
+        match self {
+            Self::North => (-1, 0),
+            Self::East => (0, 1),
+            Self::South => (1, 0),
+            Self::West => (0, -1),
+        }
+    }
+
+    /// Get the opposite direction.
+    const fn opposite(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+}
+
+/// Get the two directions a pipe connects, if the tile is a pipe.
+const fn connections(tile: char) -> Option<[Direction; 2]> {
+    match tile {
+        '|' => Some([Direction::North, Direction::South]),
+        '-' => Some([Direction::East, Direction::West]),
+        'L' => Some([Direction::North, Direction::East]),
+        'J' => Some([Direction::North, Direction::West]),
+        '7' => Some([Direction::South, Direction::West]),
+        'F' => Some([Direction::East, Direction::South]),
+        _ => None,
+    }
+}
+
+/// Get the pipe connecting two directions.
+fn pipe(directions: [Direction; 2]) -> char {
+    ['|', '-', 'L', 'J', '7', 'F']
+        .into_iter()
+        .find(|tile| {
+            connections(*tile).is_some_and(|connections| {
+                connections.contains(&directions[0]) && connections.contains(&directions[1])
+            })
+        })
+        .expect("Every pair of distinct directions has a pipe")
+}
+
+/// A field of pipes.
+#[derive(Debug)]
+struct Maze {
+    /// Tiles, with the starting one replaced by its pipe.
+    grid: Grid,
+    /// Starting position.
+    start: Position,
+    /// Positions of the main loop, in order from the starting one.
+    main_loop: Vec<Position>,
+}
+
+/// Get the neighbour in a direction, if inside the grid.
+fn follow(grid: &Grid, position: Position, direction: Direction) -> Option<Position> {
+    let (rows, columns) = direction.offset();
+    grid.offset(position, rows, columns)
+}
+
+/// Get the positions of the loop going through the starting tile, in order.
+fn trace_loop(grid: &Grid, start: Position) -> Result<Vec<Position>, InvalidInput> {
+    let mut positions = vec![start];
+    let mut direction = connections(grid[start]).ok_or(InvalidInput::BrokenLoop(start))?[0];
+    let mut position = follow(grid, start, direction).ok_or(InvalidInput::BrokenLoop(start))?;
+    while position != start {
+        positions.push(position);
+        // Enter the tile by one connection, and leave it by the other one.
+        let [first, second] = connections(grid[position])
+            .filter(|connections| connections.contains(&direction.opposite()))
+            .ok_or(InvalidInput::BrokenLoop(position))?;
+        direction = if first == direction.opposite() {
+            second
+        } else {
+            first
+        };
+        position = follow(grid, position, direction).ok_or(InvalidInput::BrokenLoop(position))?;
+    }
+    Ok(positions)
+}
+
+impl Maze {
+    /// Get the position of the loop farthest from the start, and its distance.
+    fn farthest(&self) -> (Position, usize) {
+        let steps = self.main_loop.len() / 2;
+        (self.main_loop[steps], steps)
+    }
+
+    /// Count the tiles enclosed by the loop, with the shoelace formula and
+    ///   Pick's theorem.
+    fn enclosed_count(&self) -> usize {
+        let main_loop = &self.main_loop;
+        // Twice the area of the polygon going through the tile centers.
+        let double_area = main_loop
+            .iter()
+            .zip(main_loop.iter().cycle().skip(1))
+            .map(|(first, second)| {
+                (first.row * second.column).cast_signed()
+                    - (second.row * first.column).cast_signed()
+            })
+            .sum::<isize>()
+            .unsigned_abs();
+        // The area is the inner points plus half the boundary points minus 1.
+        (double_area + 2 - main_loop.len()) / 2
+    }
+
+    /// Get the tiles enclosed by the loop, by casting rays from the left.
+    fn enclosed(&self) -> HashSet<Position> {
+        let main_loop: HashSet<_> = self.main_loop.iter().copied().collect();
+        let mut enclosed = HashSet::new();
+        for row in 0..self.grid.height() {
+            let mut inside = false;
+            for column in 0..self.grid.width() {
+                let position = Position::new(row, column);
+                if main_loop.contains(&position) {
+                    // Only the pipes going north change the side, so that a
+                    //   run along the ray counts once if it crosses it, and
+                    //   twice or never if it only touches it.
+                    if connections(self.grid[position])
+                        .is_some_and(|connections| connections.contains(&Direction::North))
+                    {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    enclosed.insert(position);
+                }
+            }
+        }
+        enclosed
+    }
+
+    /// Draw the loop with box-drawing characters, the enclosed tiles with `I`
+    ///   and the others with `.`.
+    fn render(&self) -> Grid {
+        let main_loop: HashSet<_> = self.main_loop.iter().copied().collect();
+        let enclosed = self.enclosed();
+        let mut grid = self.grid.clone();
+        for position in self.grid.positions() {
+            grid[position] = if position == self.start {
+                START
+            } else if main_loop.contains(&position) {
+                match self.grid[position] {
+                    '|' => '│',
+                    '-' => '─',
+                    'L' => '└',
+                    'J' => '┘',
+                    '7' => '┐',
+                    _ => '┌',
+                }
+            } else if enclosed.contains(&position) {
+                'I'
+            } else {
+                '.'
+            };
+        }
+        grid
+    }
+}
+
+/// Parse the maze, inferring the pipe of the starting tile.
+impl FromStr for Maze {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grid: Grid = s.parse()?;
+
+        // Find the start.
+        let starts: Vec<_> = grid
+            .iter()
+            .filter(|(_, tile)| *tile == START)
+            .map(|(position, _)| position)
+            .collect();
+        let [start] = starts[..] else {
+            return Err(InvalidInput::StartCount(starts.len()));
+        };
+
+        // Find the neighbours connected back to the start.
+        let directions: Vec<_> = Direction::ALL
+            .into_iter()
+            .filter(|direction| {
+                let (rows, columns) = direction.offset();
+                grid.offset(start, rows, columns)
+                    .and_then(|neighbour| connections(grid[neighbour]))
+                    .is_some_and(|connections| connections.contains(&direction.opposite()))
+            })
+            .collect();
+        let [first, second] = directions[..] else {
+            return Err(InvalidInput::StartConnections(directions.len()));
+        };
+        grid[start] = pipe([first, second]);
+
+        let main_loop = trace_loop(&grid, start)?;
+        Ok(Self {
+            grid,
+            start,
+            main_loop,
+        })
+    }
+}
+
+pub fn first_part() -> usize {
+    INPUT.parse::<Maze>().expect("Invalid input").farthest().1
+}
+
+pub fn second_part() -> usize {
+    INPUT
+        .parse::<Maze>()
+        .expect("Invalid input")
+        .enclosed_count()
+}
+
+/// Run a day 10 command.
+///
+/// - `render` prints the loop with box-drawing characters and the enclosed
+///   tiles with `I`.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day10 render";
+
+    let maze: Maze = INPUT.parse().map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("render") => print!("{}", maze.render()),
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InvalidInput, Maze};
+    use crate::grid::Position;
+
+    const SQUARE: &str = "-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF
+";
+
+    const COMPLEX: &str = "7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ
+";
+
+    const SQUEEZED: &str = "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+";
+
+    const LARGER: &str = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+";
+
+    #[test]
+    fn parse() {
+        let maze: Maze = SQUARE.parse().unwrap();
+        assert_eq!(maze.start, Position::new(1, 1));
+        assert_eq!(maze.grid[maze.start], 'F');
+        let maze: Maze = COMPLEX.parse().unwrap();
+        assert_eq!(maze.grid[maze.start], 'F');
+        assert_eq!(
+            "...\n.S.\n...".parse::<Maze>().err(),
+            Some(InvalidInput::StartConnections(0))
+        );
+        assert_eq!(
+            "S-S".parse::<Maze>().err(),
+            Some(InvalidInput::StartCount(2))
+        );
+        // The loop enters a tile which does not connect back.
+        assert_eq!(
+            "S7.\n|-7\n.LJ".parse::<Maze>().err(),
+            Some(InvalidInput::BrokenLoop(Position::new(1, 1)))
+        );
+        // The loop reaches a dead end.
+        assert_eq!(
+            "S-.\n|..\n...".parse::<Maze>().err(),
+            Some(InvalidInput::BrokenLoop(Position::new(0, 2)))
+        );
+    }
+
+    #[test]
+    fn farthest() {
+        let maze: Maze = SQUARE.parse().unwrap();
+        assert_eq!(maze.farthest(), (Position::new(3, 3), 4));
+        let maze: Maze = COMPLEX.parse().unwrap();
+        assert_eq!(maze.farthest(), (Position::new(2, 4), 8));
+    }
+
+    #[test]
+    fn enclosed() {
+        for (input, expected) in [
+            ("S7\nLJ", 0),
+            (SQUARE, 1),
+            (COMPLEX, 1),
+            (SQUEEZED, 4),
+            (LARGER, 8),
+            (super::INPUT, 10),
+        ] {
+            let maze: Maze = input.parse().unwrap();
+            assert_eq!(maze.enclosed_count(), expected);
+            assert_eq!(maze.enclosed().len(), expected);
+        }
+    }
+
+    #[test]
+    fn render() {
+        let maze: Maze = SQUEEZED.parse().unwrap();
+        assert_eq!(
+            maze.render().to_string(),
+            "..........
+.S──────┐.
+.│┌────┐│.
+.││....││.
+.││....││.
+.│└─┐┌─┘│.
+.│II││II│.
+.└──┘└──┘.
+..........
+"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::grid::{self, Grid, Position};
+use std::str::FromStr;
+
+const INPUT: &str = include_str!("day11.txt");
+
+/// Tile of a galaxy.
+const GALAXY: char = '#';
+
+/// Tile of an empty space.
+const EMPTY: char = '.';
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid grid.
+    Grid(grid::InvalidInput),
+    /// Unknown tile.
+    Tile(char),
+}
+
+impl From<grid::InvalidInput> for InvalidInput {
+    fn from(error: grid::InvalidInput) -> Self {
+        Self::Grid(error)
+    }
+}
+
+/// An image of galaxies.
+#[derive(Debug)]
+struct Image {
+    /// Positions of the galaxies, row after row.
+    galaxies: Vec<Position>,
+}
+
+impl Image {
+    /// Sum the distances between every pair of galaxies, when every empty row
+    ///   and column is replaced by `factor` ones.
+    fn distance_sum(&self, factor: u64) -> u128 {
+        // The Manhattan distance is the sum of the distances on each axis.
+        let rows = self.galaxies.iter().map(|galaxy| galaxy.row).collect();
+        let columns = self.galaxies.iter().map(|galaxy| galaxy.column).collect();
+        [rows, columns]
+            .into_iter()
+            .map(|coordinates| axis_distance_sum(coordinates, factor))
+            .sum()
+    }
+}
+
+/// Sum the distances between every pair of coordinates on an axis, expanding
+///   the empty lines.
+fn axis_distance_sum(mut coordinates: Vec<usize>, factor: u64) -> u128 {
+    coordinates.sort_unstable();
+    let mut occupied = 0;
+    let mut previous = None;
+    let mut prefix_sum = 0;
+    coordinates
+        .into_iter()
+        .zip(0..)
+        .map(|(coordinate, index)| {
+            // Count the lines with a galaxy up to this one, the others are
+            //   empty and expand.
+            if previous != Some(coordinate) {
+                occupied += 1;
+                previous = Some(coordinate);
+            }
+            let empty = coordinate as u128 + 1 - occupied;
+            let expanded = occupied - 1 + empty * u128::from(factor);
+            // This coordinate is after all the previous ones.
+            let distances = expanded * index - prefix_sum;
+            prefix_sum += expanded;
+            distances
+        })
+        .sum()
+}
+
+/// Parse the image.
+impl FromStr for Image {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grid: Grid = s.parse()?;
+        let mut galaxies = Vec::new();
+        for (position, tile) in grid.iter() {
+            match tile {
+                GALAXY => galaxies.push(position),
+                EMPTY => (),
+                _ => return Err(InvalidInput::Tile(tile)),
+            }
+        }
+        Ok(Self { galaxies })
+    }
+}
+
+pub fn first_part() -> u128 {
+    INPUT
+        .parse::<Image>()
+        .expect("Invalid input")
+        .distance_sum(2)
+}
+
+pub fn second_part() -> u128 {
+    INPUT
+        .parse::<Image>()
+        .expect("Invalid input")
+        .distance_sum(1_000_000)
+}
+
+/// Run a day 11 command.
+///
+/// - `distances FACTOR` prints the sum of the distances between every pair of
+///   galaxies, when every empty row and column is replaced by `FACTOR` ones.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day11 distances FACTOR";
+
+    let image: Image = INPUT.parse().map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("distances") => {
+            let factor = args.next().and_then(|arg| arg.parse().ok()).ok_or(USAGE)?;
+            println!("{}", image.distance_sum(factor));
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Image, InvalidInput, INPUT};
+    use crate::grid::Position;
+
+    impl Position {
+        /// Get the Manhattan distance to another position.
+        const fn manhattan(self, other: Self) -> usize {
+            self.row.abs_diff(other.row) + self.column.abs_diff(other.column)
+        }
+    }
+
+    impl Image {
+        /// Sum the distances by expanding every galaxy then trying every pair.
+        fn brute_force_distance_sum(&self, factor: usize) -> usize {
+            let expand = |coordinate: usize, occupied: &[usize]| {
+                (0..coordinate)
+                    .map(|line| if occupied.contains(&line) { 1 } else { factor })
+                    .sum()
+            };
+            let rows: Vec<_> = self.galaxies.iter().map(|galaxy| galaxy.row).collect();
+            let columns: Vec<_> = self.galaxies.iter().map(|galaxy| galaxy.column).collect();
+            let galaxies: Vec<_> = self
+                .galaxies
+                .iter()
+                .map(|galaxy| {
+                    Position::new(expand(galaxy.row, &rows), expand(galaxy.column, &columns))
+                })
+                .collect();
+            galaxies
+                .iter()
+                .enumerate()
+                .flat_map(|(index, first)| {
+                    galaxies[index + 1..]
+                        .iter()
+                        .map(|second| first.manhattan(*second))
+                })
+                .sum()
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let image: Image = INPUT.parse().unwrap();
+        assert_eq!(image.galaxies.len(), 9);
+        assert_eq!(image.galaxies[0], Position::new(0, 3));
+        assert_eq!(
+            ".#\n#x".parse::<Image>().err(),
+            Some(InvalidInput::Tile('x'))
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let image: Image = INPUT.parse().unwrap();
+        assert_eq!(image.distance_sum(2), 374);
+        assert_eq!(image.distance_sum(10), 1030);
+        assert_eq!(image.distance_sum(100), 8410);
+        for factor in [0, 1, 2, 10, 100] {
+            assert_eq!(
+                image.distance_sum(u64::try_from(factor).unwrap()),
+                image.brute_force_distance_sum(factor) as u128
+            );
+        }
+        let image: Image = "#..\n...\n..#\n..#".parse().unwrap();
+        assert_eq!(image.distance_sum(u64::MAX), 4 * u128::from(u64::MAX) + 6);
+    }
+}
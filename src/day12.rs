@@ -0,0 +1,326 @@
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+const INPUT: &str = include_str!("day12.txt");
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid integer.
+    ParseInt(ParseIntError),
+    /// Unknown spring condition.
+    Spring(char),
+    /// Another parsing error.
+    Other,
+}
+
+impl From<ParseIntError> for InvalidInput {
+    fn from(error: ParseIntError) -> Self {
+        Self::ParseInt(error)
+    }
+}
+
+/// The condition of a spring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spring {
+    /// Working.
+    Operational,
+    /// Broken.
+    Damaged,
+    /// Either.
+    Unknown,
+}
+
+/// Parse a spring condition.
+impl TryFrom<char> for Spring {
+    type Error = InvalidInput;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Self::Operational),
+            '#' => Ok(Self::Damaged),
+            '?' => Ok(Self::Unknown),
+            _ => Err(InvalidInput::Spring(c)),
+        }
+    }
+}
+
+/// Print a spring condition.
+impl fmt::Display for Spring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Self::Operational => '.',
+            Self::Damaged => '#',
+            Self::Unknown => '?',
+        };
+        write!(f, "{c}")
+    }
+}
+
+/// A row of springs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    /// Conditions of the springs.
+    springs: Vec<Spring>,
+    /// Sizes of the contiguous groups of damaged springs, in order.
+    groups: Vec<usize>,
+}
+
+impl Row {
+    /// Repeat the springs, separated by unknown ones, and the groups.
+    fn unfold(&self, times: usize) -> Self {
+        let springs = vec![self.springs.as_slice(); times].join(&Spring::Unknown);
+        let groups = self.groups.repeat(times);
+        Self { springs, groups }
+    }
+
+    /// Get the index after a group of damaged springs starting at an index,
+    ///   if it can be placed there.
+    fn place(&self, start: usize, size: usize) -> Option<usize> {
+        let end = start.checked_add(size)?;
+        // The group must fit, without operational springs, and be followed by
+        //   the end of the row or a spring which can be operational.
+        (end <= self.springs.len()
+            && !self.springs[start..end].contains(&Spring::Operational)
+            && self.springs.get(end) != Some(&Spring::Damaged))
+        .then(|| (end + 1).min(self.springs.len()))
+    }
+
+    /// Count the arrangements matching both the conditions and the groups.
+    fn arrangements(&self) -> u64 {
+        /// Count the arrangements of the springs from an index with the
+        ///   groups from another, remembering the counts.
+        fn count(row: &Row, spring: usize, group: usize, memo: &mut [Vec<Option<u64>>]) -> u64 {
+            if let Some(count) = memo[spring][group] {
+                return count;
+            }
+            let result = if spring == row.springs.len() {
+                u64::from(group == row.groups.len())
+            } else {
+                let condition = row.springs[spring];
+                // The spring is operational.
+                let operational = if condition == Spring::Damaged {
+                    0
+                } else {
+                    count(row, spring + 1, group, memo)
+                };
+                // The spring starts the next group.
+                let damaged = if condition == Spring::Operational {
+                    0
+                } else {
+                    row.groups
+                        .get(group)
+                        .and_then(|size| row.place(spring, *size))
+                        .map_or(0, |next| count(row, next, group + 1, memo))
+                };
+                operational + damaged
+            };
+            memo[spring][group] = Some(result);
+            result
+        }
+
+        let mut memo = vec![vec![None; self.groups.len() + 1]; self.springs.len() + 1];
+        count(self, 0, 0, &mut memo)
+    }
+
+    /// List the arrangements, one after the other, which is only sensible for
+    ///   small rows.
+    fn enumerate(&self) -> Vec<String> {
+        /// Add the arrangements of the springs from an index with the groups
+        ///   from another, after a prefix.
+        fn search(
+            row: &Row,
+            spring: usize,
+            group: usize,
+            prefix: &mut String,
+            result: &mut Vec<String>,
+        ) {
+            if spring == row.springs.len() {
+                if group == row.groups.len() {
+                    result.push(prefix.clone());
+                }
+                return;
+            }
+            let len = prefix.len();
+            let condition = row.springs[spring];
+            // The spring is operational.
+            if condition != Spring::Damaged {
+                prefix.push('.');
+                search(row, spring + 1, group, prefix, result);
+                prefix.truncate(len);
+            }
+            // The spring starts the next group.
+            if condition != Spring::Operational {
+                if let Some((size, next)) = row
+                    .groups
+                    .get(group)
+                    .and_then(|size| Some((size, row.place(spring, *size)?)))
+                {
+                    prefix.push_str(&"#".repeat(*size));
+                    prefix.push_str(&".".repeat(next - spring - size));
+                    search(row, next, group + 1, prefix, result);
+                    prefix.truncate(len);
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        search(self, 0, 0, &mut String::new(), &mut result);
+        result
+    }
+}
+
+/// Parse a row.
+impl FromStr for Row {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split the springs from the groups.
+        let (springs, groups) = s.split_once(' ').ok_or(InvalidInput::Other)?;
+        // Parse the springs.
+        let springs = springs
+            .chars()
+            .map(Spring::try_from)
+            .collect::<Result<_, _>>()?;
+        // Parse the groups.
+        let groups = groups
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if groups.contains(&0) {
+            return Err(InvalidInput::Other);
+        }
+        Ok(Self { springs, groups })
+    }
+}
+
+/// Print a row like in the input.
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for spring in &self.springs {
+            write!(f, "{spring}")?;
+        }
+        let groups: Vec<_> = self.groups.iter().map(ToString::to_string).collect();
+        write!(f, " {}", groups.join(","))
+    }
+}
+
+/// Parse the rows, one per line.
+fn parse_rows(s: &str) -> Result<Vec<Row>, InvalidInput> {
+    s.trim().lines().map(str::parse).collect()
+}
+
+pub fn first_part() -> u64 {
+    // Parse the input.
+    parse_rows(INPUT)
+        .expect("Invalid input")
+        .iter()
+        // Count the arrangements.
+        .map(Row::arrangements)
+        // Sum.
+        .sum()
+}
+
+pub fn second_part() -> u64 {
+    // Parse the input.
+    parse_rows(INPUT)
+        .expect("Invalid input")
+        .iter()
+        // Unfold the rows.
+        .map(|row| row.unfold(5))
+        // Count the arrangements.
+        .map(|row| row.arrangements())
+        // Sum.
+        .sum()
+}
+
+/// Run a day 12 command.
+///
+/// - `enumerate LINE [UNFOLD]` prints the arrangements of the row of a line
+///   (starting at 1), unfolded the given amount of times (1 by default), as
+///   long as there are at most 1000 of them.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day12 enumerate LINE [UNFOLD]";
+    /// Most arrangements to list.
+    const LIMIT: u64 = 1_000;
+
+    let rows = parse_rows(INPUT).map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("enumerate") => {
+            let line: usize = args.next().and_then(|arg| arg.parse().ok()).ok_or(USAGE)?;
+            let row = line
+                .checked_sub(1)
+                .and_then(|index| rows.get(index))
+                .ok_or_else(|| format!("No line {line}"))?;
+            let times = args
+                .next()
+                .map_or(Ok(1), |arg| arg.parse())
+                .map_err(|_| USAGE)?;
+            let row = row.unfold(times);
+            let count = row.arrangements();
+            if count > LIMIT {
+                return Err(format!("{row} has {count} arrangements, more than {LIMIT}"));
+            }
+            println!("{row}");
+            for arrangement in row.enumerate() {
+                println!("{arrangement}");
+            }
+            println!("{count} arrangements");
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_rows, InvalidInput, Row, INPUT};
+
+    #[test]
+    fn parse() {
+        let rows = parse_rows(INPUT).unwrap();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[1].groups, [1, 1, 3]);
+        assert_eq!(rows[1].to_string(), ".??..??...?##. 1,1,3");
+        assert_eq!(
+            ".#x 1".parse::<Row>().err(),
+            Some(InvalidInput::Spring('x'))
+        );
+        assert_eq!("?? 1,0".parse::<Row>().err(), Some(InvalidInput::Other));
+    }
+
+    #[test]
+    fn arrangements() {
+        let rows = parse_rows(INPUT).unwrap();
+        let counts: Vec<_> = rows.iter().map(Row::arrangements).collect();
+        assert_eq!(counts, [1, 4, 1, 1, 4, 10]);
+        let counts: Vec<_> = rows
+            .iter()
+            .map(|row| row.unfold(5).arrangements())
+            .collect();
+        assert_eq!(counts, [1, 16_384, 1, 16, 2_500, 506_250]);
+        assert_eq!(
+            ".# 1".parse::<Row>().unwrap().unfold(2).to_string(),
+            ".#?.# 1,1"
+        );
+        assert_eq!("#.# 2".parse::<Row>().unwrap().arrangements(), 0);
+        let row: Row = format!("#?? 1,{}", usize::MAX).parse().unwrap();
+        assert_eq!(row.arrangements(), 0);
+    }
+
+    #[test]
+    fn enumerate() {
+        let row: Row = "?###???????? 3,2,1".parse().unwrap();
+        let arrangements = row.enumerate();
+        assert_eq!(arrangements.len(), 10);
+        assert_eq!(arrangements[0], ".###....##.#");
+        assert_eq!(arrangements[9], ".###.##.#...");
+        // Every row agrees with the count.
+        for row in parse_rows(INPUT).unwrap() {
+            assert_eq!(row.enumerate().len() as u64, row.arrangements());
+            assert_eq!(
+                row.unfold(2).enumerate().len() as u64,
+                row.unfold(2).arrangements()
+            );
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::grid::{self, Grid};
+use std::str::FromStr;
+
+const INPUT: &str = include_str!("day13.txt");
+
+/// Tile of an ash.
+const ASH: char = '.';
+
+/// Tile of a rock.
+const ROCK: char = '#';
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid grid.
+    Grid(grid::InvalidInput),
+    /// Unknown tile.
+    Tile(char),
+    /// A pattern has more rows or columns than bits in a mask.
+    TooLarge,
+}
+
+impl From<grid::InvalidInput> for InvalidInput {
+    fn from(error: grid::InvalidInput) -> Self {
+        Self::Grid(error)
+    }
+}
+
+/// A pattern of ash and rocks.
+#[derive(Debug, PartialEq, Eq)]
+struct Pattern {
+    /// Rows, as masks of rocks from the left.
+    rows: Vec<u64>,
+    /// Columns, as masks of rocks from the top.
+    columns: Vec<u64>,
+}
+
+/// Get the mask of the rocks of a line, setting a bit per rock.
+fn mask(line: impl Iterator<Item = char>) -> Result<u64, InvalidInput> {
+    line.zip(0..).try_fold(0, |mask, (tile, index)| match tile {
+        ROCK => Ok(mask | 1 << index),
+        ASH => Ok(mask),
+        _ => Err(InvalidInput::Tile(tile)),
+    })
+}
+
+/// Find a mirror between two lines, such that the reflected lines differ by
+///   exactly some cells, and get the amount of lines before it.
+fn mirror(lines: &[u64], smudges: u32) -> Option<usize> {
+    (1..lines.len()).find(|mirror| {
+        // Compare the lines from the mirror outwards, until one side ends.
+        lines[..*mirror]
+            .iter()
+            .rev()
+            .zip(&lines[*mirror..])
+            .map(|(first, second)| (first ^ second).count_ones())
+            .sum::<u32>()
+            == smudges
+    })
+}
+
+impl Pattern {
+    /// Summarize the mirror with some smudges: the amount of columns left to
+    ///   a vertical one, or 100 times the amount of rows above a horizontal
+    ///   one.
+    fn summarize(&self, smudges: u32) -> Option<usize> {
+        mirror(&self.columns, smudges)
+            .or_else(|| mirror(&self.rows, smudges).map(|rows| 100 * rows))
+    }
+}
+
+/// Parse a pattern.
+impl FromStr for Pattern {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grid: Grid = s.parse()?;
+        if grid.width() > 64 || grid.height() > 64 {
+            return Err(InvalidInput::TooLarge);
+        }
+
+        let rows = grid
+            .rows()
+            .map(|row| mask(row.iter().copied()))
+            .collect::<Result<_, _>>()?;
+        let columns = grid.columns().map(mask).collect::<Result<_, _>>()?;
+        Ok(Self { rows, columns })
+    }
+}
+
+/// Parse the patterns, separated by blank lines.
+fn parse_patterns(s: &str) -> Result<Vec<Pattern>, InvalidInput> {
+    s.trim().split("\n\n").map(str::parse).collect()
+}
+
+/// Sum the summaries of the mirrors with some smudges.
+fn sum_summaries(s: &str, smudges: u32) -> usize {
+    // Parse the input.
+    parse_patterns(s)
+        .expect("Invalid input")
+        .iter()
+        // Summarize the mirror.
+        .map(|pattern| pattern.summarize(smudges).expect("No mirror"))
+        // Sum.
+        .sum()
+}
+
+pub fn first_part() -> usize {
+    sum_summaries(INPUT, 0)
+}
+
+pub fn second_part() -> usize {
+    sum_summaries(INPUT, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mirror, parse_patterns, sum_summaries, InvalidInput, Pattern, INPUT};
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "#.\n.#\n##".parse(),
+            Ok(Pattern {
+                rows: vec![0b01, 0b10, 0b11],
+                columns: vec![0b101, 0b110],
+            })
+        );
+        assert_eq!("#x".parse::<Pattern>(), Err(InvalidInput::Tile('x')));
+        assert_eq!(
+            ".".repeat(65).parse::<Pattern>(),
+            Err(InvalidInput::TooLarge)
+        );
+        assert_eq!(parse_patterns(INPUT).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn mirrors() {
+        assert_eq!(mirror(&[1, 2, 2, 1, 3], 0), Some(2));
+        assert_eq!(mirror(&[1, 2, 3], 0), None);
+        assert_eq!(mirror(&[1, 2, 3, 3], 0), Some(3));
+        assert_eq!(mirror(&[0b11, 0b01], 1), Some(1));
+        assert_eq!(mirror(&[0b11, 0b00], 1), None);
+        let patterns = parse_patterns(INPUT).unwrap();
+        assert_eq!(patterns[0].summarize(0), Some(5));
+        assert_eq!(patterns[1].summarize(0), Some(400));
+        assert_eq!(patterns[0].summarize(1), Some(300));
+        assert_eq!(patterns[1].summarize(1), Some(100));
+    }
+
+    #[test]
+    fn summaries() {
+        assert_eq!(sum_summaries(INPUT, 0), 405);
+        assert_eq!(sum_summaries(INPUT, 1), 400);
+    }
+}
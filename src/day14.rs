@@ -0,0 +1,289 @@
+use crate::grid::{self, Grid, Position};
+use std::{collections::HashMap, str::FromStr};
+
+const INPUT: &str = include_str!("day14.txt");
+
+/// Tile of a rounded rock, which rolls.
+const ROUNDED: char = 'O';
+
+/// Tile of a cube-shaped rock, which stays in place.
+const CUBE: char = '#';
+
+/// Tile of an empty space.
+const EMPTY: char = '.';
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid grid.
+    Grid(grid::InvalidInput),
+    /// Unknown tile.
+    Tile(char),
+}
+
+impl From<grid::InvalidInput> for InvalidInput {
+    fn from(error: grid::InvalidInput) -> Self {
+        Self::Grid(error)
+    }
+}
+
+/// A direction to tilt the platform to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Up.
+    North,
+    /// Left.
+    West,
+    /// Down.
+    South,
+    /// Right.
+    East,
+}
+
+impl Direction {
+    /// The directions of a spin cycle, in order.
+    const SPIN: [Self; 4] = [Self::North, Self::West, Self::South, Self::East];
+}
+
+/// A platform of rocks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Platform(Grid);
+
+/// The spin cycles of a platform, which end up repeating.
+#[derive(Debug)]
+struct SpinCycles {
+    /// Spin cycles before the repetition.
+    start: usize,
+    /// Spin cycles between two repetitions.
+    length: usize,
+    /// Platforms after every spin cycle, from none until the repetition.
+    platforms: Vec<Platform>,
+}
+
+impl SpinCycles {
+    /// Get the platform after some spin cycles.
+    fn after(&self, spins: usize) -> &Platform {
+        let index = if spins < self.start {
+            spins
+        } else {
+            self.start + (spins - self.start) % self.length
+        };
+        &self.platforms[index]
+    }
+}
+
+impl Platform {
+    /// Get the lines of positions along a direction, each starting from the
+    ///   edge the rocks roll to.
+    fn lines(&self, direction: Direction) -> Vec<Vec<Position>> {
+        let (width, height) = (self.0.width(), self.0.height());
+        match direction {
+            Direction::North => (0..width)
+                .map(|column| (0..height).map(|row| Position::new(row, column)).collect())
+                .collect(),
+            Direction::South => (0..width)
+                .map(|column| {
+                    (0..height)
+                        .rev()
+                        .map(|row| Position::new(row, column))
+                        .collect()
+                })
+                .collect(),
+            Direction::West => (0..height)
+                .map(|row| {
+                    (0..width)
+                        .map(|column| Position::new(row, column))
+                        .collect()
+                })
+                .collect(),
+            Direction::East => (0..height)
+                .map(|row| {
+                    (0..width)
+                        .rev()
+                        .map(|column| Position::new(row, column))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Roll all the rounded rocks in a direction.
+    fn tilt(&mut self, direction: Direction) {
+        for line in self.lines(direction) {
+            // Index of the first free position of the line.
+            let mut free = 0;
+            for (index, position) in line.iter().enumerate() {
+                match self.0[*position] {
+                    CUBE => free = index + 1,
+                    ROUNDED => {
+                        self.0[*position] = EMPTY;
+                        self.0[line[free]] = ROUNDED;
+                        free += 1;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Tilt in every direction of a spin cycle.
+    fn spin(&mut self) {
+        for direction in Direction::SPIN {
+            self.tilt(direction);
+        }
+    }
+
+    /// Spin until the platform repeats.
+    fn spin_cycles(&self) -> SpinCycles {
+        let mut platforms = vec![self.clone()];
+        let mut seen = HashMap::from([(self.clone(), 0)]);
+        let mut platform = self.clone();
+        loop {
+            platform.spin();
+            if let Some(start) = seen.get(&platform) {
+                return SpinCycles {
+                    start: *start,
+                    length: platforms.len() - start,
+                    platforms,
+                };
+            }
+            seen.insert(platform.clone(), platforms.len());
+            platforms.push(platform.clone());
+        }
+    }
+
+    /// Compute the load on the north support beams: each rounded rock weighs
+    ///   its distance to the south edge, inclusive.
+    fn north_load(&self) -> usize {
+        let height = self.0.height();
+        self.0
+            .iter()
+            .filter(|(_, tile)| *tile == ROUNDED)
+            .map(|(position, _)| height - position.row)
+            .sum()
+    }
+}
+
+/// Parse the platform.
+impl FromStr for Platform {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grid: Grid = s.parse()?;
+        if let Some((_, tile)) = grid
+            .iter()
+            .find(|(_, tile)| ![ROUNDED, CUBE, EMPTY].contains(tile))
+        {
+            return Err(InvalidInput::Tile(tile));
+        }
+        Ok(Self(grid))
+    }
+}
+
+/// Amount of spin cycles of the second part.
+const SPINS: usize = 1_000_000_000;
+
+pub fn first_part() -> usize {
+    let mut platform: Platform = INPUT.parse().expect("Invalid input");
+    platform.tilt(Direction::North);
+    platform.north_load()
+}
+
+pub fn second_part() -> usize {
+    let platform: Platform = INPUT.parse().expect("Invalid input");
+    platform.spin_cycles().after(SPINS).north_load()
+}
+
+/// Run a day 14 command.
+///
+/// - `cycle` prints when the spin cycles start repeating, the north load
+///   after each spin cycle of the repetition, and the platform after one
+///   billion spin cycles.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day14 cycle";
+
+    let platform: Platform = INPUT.parse().map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("cycle") => {
+            let cycles = platform.spin_cycles();
+            println!(
+                "The platform repeats every {} spin cycles after {} spin cycles",
+                cycles.length, cycles.start
+            );
+            for (spins, platform) in cycles.platforms.iter().enumerate().skip(cycles.start) {
+                println!(
+                    "After {spins} spin cycles, the north load is {}",
+                    platform.north_load()
+                );
+            }
+            let platform = cycles.after(SPINS);
+            println!(
+                "After {SPINS} spin cycles, the north load is {}:",
+                platform.north_load()
+            );
+            print!("{}", platform.0);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, InvalidInput, Platform, INPUT, SPINS};
+
+    #[test]
+    fn tilt() {
+        let mut platform: Platform = INPUT.parse().unwrap();
+        platform.tilt(Direction::North);
+        assert_eq!(
+            platform.0.to_string(),
+            "OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#....
+"
+        );
+        assert_eq!(platform.north_load(), 136);
+        let mut platform: Platform = "O.#O.\n..O.O".parse().unwrap();
+        platform.tilt(Direction::East);
+        assert_eq!(platform.0.to_string(), ".O#.O\n...OO\n");
+        platform.tilt(Direction::South);
+        assert_eq!(platform.0.to_string(), "..#.O\n.O.OO\n");
+        platform.tilt(Direction::West);
+        assert_eq!(platform.0.to_string(), "..#O.\nOOO..\n");
+        assert_eq!("O.x".parse::<Platform>(), Err(InvalidInput::Tile('x')));
+    }
+
+    #[test]
+    fn spin_cycles() {
+        let platform: Platform = INPUT.parse().unwrap();
+        let mut spun = platform.clone();
+        spun.spin();
+        assert_eq!(
+            spun.0.to_string(),
+            ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+"
+        );
+        let cycles = platform.spin_cycles();
+        assert_eq!((cycles.start, cycles.length), (3, 7));
+        assert_eq!(cycles.after(1), &spun);
+        assert_eq!(cycles.after(10), cycles.after(3));
+        assert_eq!(cycles.after(SPINS).north_load(), 64);
+    }
+}
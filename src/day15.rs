@@ -0,0 +1,238 @@
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+const INPUT: &str = include_str!("day15.txt");
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid integer.
+    ParseInt(ParseIntError),
+    /// The label is empty or not ASCII.
+    Label(String),
+    /// Another parsing error.
+    Other,
+}
+
+impl From<ParseIntError> for InvalidInput {
+    fn from(error: ParseIntError) -> Self {
+        Self::ParseInt(error)
+    }
+}
+
+/// Run the HASH algorithm on a string.
+fn hash(s: &str) -> u8 {
+    s.bytes().fold(0, |current, byte| {
+        current.wrapping_add(byte).wrapping_mul(17)
+    })
+}
+
+/// A step of the initialization sequence.
+#[derive(Debug, PartialEq, Eq)]
+enum Operation {
+    /// Remove the lens with a label from its box, if any.
+    Remove {
+        /// Label of the lens.
+        label: String,
+    },
+    /// Replace the lens with the same label in its box, or add it at the end.
+    Insert {
+        /// The lens.
+        lens: Lens,
+    },
+}
+
+impl Operation {
+    /// Get the label of the lens.
+    fn label(&self) -> &str {
+        match self {
+            Self::Remove { label }
+            | Self::Insert {
+                lens: Lens { label, .. },
+            } => label,
+        }
+    }
+}
+
+/// Parse a step, `label-` or `label=N`.
+impl FromStr for Operation {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let operation = if let Some(label) = s.strip_suffix('-') {
+            Self::Remove {
+                label: label.to_owned(),
+            }
+        } else {
+            let (label, focal_length) = s.split_once('=').ok_or(InvalidInput::Other)?;
+            Self::Insert {
+                lens: Lens {
+                    label: label.to_owned(),
+                    focal_length: focal_length.parse()?,
+                },
+            }
+        };
+        // The labels are hashed by bytes.
+        let label = operation.label();
+        if label.is_empty() || !label.is_ascii() {
+            return Err(InvalidInput::Label(label.to_owned()));
+        }
+        Ok(operation)
+    }
+}
+
+/// A lens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lens {
+    /// Label.
+    label: String,
+    /// Focal length.
+    focal_length: u8,
+}
+
+/// The boxes of lenses, indexed by the hash of the labels.
+#[derive(Debug)]
+struct Boxes(Vec<Vec<Lens>>);
+
+impl Boxes {
+    /// Create empty boxes.
+    fn new() -> Self {
+        Self(vec![Vec::new(); 256])
+    }
+
+    /// Apply a step.
+    fn apply(&mut self, operation: Operation) {
+        let lenses = &mut self.0[usize::from(hash(operation.label()))];
+        match operation {
+            Operation::Remove { label } => lenses.retain(|lens| lens.label != label),
+            Operation::Insert { lens } => {
+                match lenses.iter_mut().find(|other| other.label == lens.label) {
+                    Some(other) => *other = lens,
+                    None => lenses.push(lens),
+                }
+            }
+        }
+    }
+
+    /// Compute the total focusing power of the lenses.
+    fn focusing_power(&self) -> usize {
+        self.0
+            .iter()
+            .zip(1..)
+            .flat_map(|(lenses, box_number)| {
+                lenses
+                    .iter()
+                    .zip(1..)
+                    .map(move |(lens, slot)| box_number * slot * usize::from(lens.focal_length))
+            })
+            .sum()
+    }
+}
+
+/// Print the boxes which contain lenses, one per line.
+impl fmt::Display for Boxes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, lenses) in self.0.iter().enumerate() {
+            if lenses.is_empty() {
+                continue;
+            }
+            write!(f, "Box {index}:")?;
+            for lens in lenses {
+                write!(f, " [{} {}]", lens.label, lens.focal_length)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split the initialization sequence, ignoring the newlines.
+fn steps(s: &str) -> impl Iterator<Item = String> + '_ {
+    s.split(',').map(|step| step.replace('\n', ""))
+}
+
+/// Run the initialization sequence.
+fn initialize(s: &str) -> Result<Boxes, InvalidInput> {
+    let mut boxes = Boxes::new();
+    for step in steps(s) {
+        boxes.apply(step.parse()?);
+    }
+    Ok(boxes)
+}
+
+pub fn first_part() -> u32 {
+    // Split the steps.
+    steps(INPUT)
+        // Hash them.
+        .map(|step| u32::from(hash(&step)))
+        // Sum.
+        .sum()
+}
+
+pub fn second_part() -> u32 {
+    let power = initialize(INPUT).expect("Invalid input").focusing_power();
+    u32::try_from(power).expect("Focusing power overflow")
+}
+
+/// Run a day 15 command.
+///
+/// - `boxes` prints the lenses of each non-empty box after the
+///   initialization sequence.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day15 boxes";
+
+    let boxes = initialize(INPUT).map_err(|error| format!("Invalid input: {error:?}"))?;
+    match args.next().as_deref() {
+        Some("boxes") => print!("{boxes}"),
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash, initialize, steps, InvalidInput, Lens, Operation, INPUT};
+
+    #[test]
+    fn hashes() {
+        assert_eq!(hash("HASH"), 52);
+        assert_eq!(hash("rn"), 0);
+        assert_eq!(hash("qp"), 1);
+        let hashes: Vec<_> = steps(INPUT).map(|step| hash(&step)).collect();
+        assert_eq!(hashes, [30, 253, 97, 47, 14, 180, 9, 197, 48, 214, 231]);
+        assert_eq!(hashes.iter().copied().map(u32::from).sum::<u32>(), 1320);
+        assert_eq!(steps("r\nn=1,\ncm-").collect::<Vec<_>>(), ["rn=1", "cm-"]);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "rn=1".parse(),
+            Ok(Operation::Insert {
+                lens: Lens {
+                    label: "rn".into(),
+                    focal_length: 1
+                }
+            })
+        );
+        assert_eq!("cm-".parse(), Ok(Operation::Remove { label: "cm".into() }));
+        assert_eq!(
+            "=1".parse::<Operation>(),
+            Err(InvalidInput::Label(String::new()))
+        );
+        assert_eq!("cm".parse::<Operation>(), Err(InvalidInput::Other));
+        assert!(matches!(
+            "cm=x".parse::<Operation>(),
+            Err(InvalidInput::ParseInt(_))
+        ));
+    }
+
+    #[test]
+    fn boxes() {
+        let boxes = initialize(INPUT).unwrap();
+        assert_eq!(
+            boxes.to_string(),
+            "Box 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]\n"
+        );
+        assert_eq!(boxes.focusing_power(), 145);
+    }
+}
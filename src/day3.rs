@@ -1,151 +1,770 @@
+use std::{collections::BTreeMap, fmt::Write, fs, ops::Range, str::FromStr};
+
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Table};
+
+use crate::grid::{self, Grid, Position};
+
 const INPUT: &str = include_str!("day3.txt");
 
+/// The gear symbol.
+const GEAR: char = '*';
+
 const fn is_symbol(c: char) -> bool {
     c != '.' && !c.is_ascii_digit()
 }
 
-pub fn first_part() -> u32 {
-    // Split the input into lines.
-    let lines: Vec<_> = INPUT.trim().lines().collect();
+/// Invalid query argument.
+#[derive(Debug, PartialEq)]
+struct InvalidInput;
 
-    // Find numbers.
-    lines
-        .iter()
-        .enumerate()
-        .flat_map(|(line, s)| {
-            // Find the numbers in the current line.
-            s.chars().enumerate().filter_map(move |(column, c)| {
-                // Get the previous character.
-                let prev = s[..column].chars().last();
-                // If the current character is a digit but not the previous one.
-                if c.is_ascii_digit() && (prev.map_or(true, |c| !c.is_ascii_digit())) {
-                    // Get the whole number.
-                    let number = s[column..]
-                        .split_once(|c: char| !c.is_ascii_digit())
-                        .unwrap_or((&s[column..], ""))
-                        .0;
-                    Some((line, column, number))
-                } else {
-                    None
-                }
-            })
-        })
-        // Filter the number which are adjacent to a symbol.
-        .filter_map(|(line, column, number)| {
-            // Lines range.
-            let from_line = line.saturating_sub(1);
-            let to_line = line + 1;
-            // Columns range.
-            let from_col = column.saturating_sub(1);
-            let to_col = column + number.len();
-            // Find symbols in the ranges.
-            let is_adjacent = (from_line..=to_line).any(|line| {
-                (from_col..=to_col).any(|column| {
-                    lines
-                        .get(line)
-                        .and_then(|line| line.chars().nth(column))
-                        .map_or(false, is_symbol)
-                })
-            });
-            if is_adjacent {
-                Some(number.parse::<u32>().unwrap_or(0))
-            } else {
-                None
+/// Invalid schematic.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidSchematic {
+    /// Invalid grid.
+    Grid(grid::InvalidInput),
+    /// A number does not fit.
+    Overflow {
+        /// Index of the row.
+        row: usize,
+        /// Columns of the number.
+        columns: Range<usize>,
+    },
+}
+
+impl From<grid::InvalidInput> for InvalidSchematic {
+    fn from(error: grid::InvalidInput) -> Self {
+        Self::Grid(error)
+    }
+}
+
+/// A set of symbols to query.
+#[derive(Debug, PartialEq)]
+enum SymbolSet {
+    /// Any symbol.
+    Any,
+    /// Only the listed symbols.
+    OneOf(Vec<char>),
+}
+
+impl SymbolSet {
+    /// Check whether a symbol belongs to the set.
+    fn contains(&self, symbol: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::OneOf(symbols) => symbols.contains(&symbol),
+        }
+    }
+}
+
+/// Parse a set of symbols: `any` or the symbols themselves (such as `*#`).
+impl FromStr for SymbolSet {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "any" {
+            Ok(Self::Any)
+        } else if !s.is_empty() && s.chars().all(is_symbol) {
+            Ok(Self::OneOf(s.chars().collect()))
+        } else {
+            Err(InvalidInput)
+        }
+    }
+}
+
+/// A constraint on the amount of numbers adjacent to a symbol.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Neighbours {
+    /// Exactly this amount of numbers.
+    Exactly(usize),
+    /// At least this amount of numbers.
+    AtLeast(usize),
+}
+
+impl Neighbours {
+    /// Check whether an amount of numbers satisfies the constraint.
+    const fn accepts(self, count: usize) -> bool {
+        match self {
+            Self::Exactly(n) => count == n,
+            Self::AtLeast(n) => count >= n,
+        }
+    }
+}
+
+/// Parse a constraint: `=N` or `>=N`.
+impl FromStr for Neighbours {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split the operator from the amount.
+        let (operator, n) = s.split_at(s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len()));
+        let n = n.parse().map_err(|_| InvalidInput)?;
+        match operator {
+            "=" => Ok(Self::Exactly(n)),
+            ">=" => Ok(Self::AtLeast(n)),
+            _ => Err(InvalidInput),
+        }
+    }
+}
+
+/// How to combine the numbers adjacent to a symbol.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Aggregation {
+    /// Add the numbers.
+    Sum,
+    /// Multiply the numbers.
+    Product,
+}
+
+/// Parse an aggregation: `sum` or `product`.
+impl FromStr for Aggregation {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Self::Sum),
+            "product" => Ok(Self::Product),
+            _ => Err(InvalidInput),
+        }
+    }
+}
+
+/// Highlight of a rendered cell.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Highlight {
+    /// A digit of a number adjacent to a symbol.
+    PartNumber,
+    /// A digit of a number without adjacent symbol.
+    IgnoredNumber,
+    /// A gear.
+    Gear,
+    /// Any other symbol.
+    Symbol,
+    /// An empty cell.
+    Empty,
+}
+
+/// Rendering format.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    /// Text with ANSI colours.
+    Ansi,
+    /// HTML document.
+    Html,
+}
+
+impl Format {
+    /// Text before the rendering.
+    const fn header(self) -> &'static str {
+        match self {
+            Self::Ansi => "",
+            Self::Html => {
+                "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<style>
+body { background: #0f0f23; color: #cccccc; }
+.part { color: #00cc00; }
+.ignored { color: #cc0000; }
+.gear { color: #ffff66; font-weight: bold; }
+.symbol { color: #00cccc; }
+.empty { color: #555555; }
+</style>
+</head>
+<body>
+<pre>
+"
             }
-        })
-        // Sum.
-        .sum()
+        }
+    }
+
+    /// Text after the rendering.
+    const fn footer(self) -> &'static str {
+        match self {
+            Self::Ansi => "",
+            Self::Html => "</pre>\n</body>\n</html>\n",
+        }
+    }
+
+    /// Escape the text of some cells.
+    fn escape(self, text: &str) -> String {
+        match self {
+            Self::Ansi => text.to_owned(),
+            Self::Html => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        }
+    }
+
+    /// Text before a group of highlighted cells.
+    const fn open(self, highlight: Highlight) -> &'static str {
+        match (self, highlight) {
+            (Self::Ansi, Highlight::PartNumber) => "\x1b[32m",
+            (Self::Ansi, Highlight::IgnoredNumber) => "\x1b[31m",
+            (Self::Ansi, Highlight::Gear) => "\x1b[1;33m",
+            (Self::Ansi, Highlight::Symbol) => "\x1b[36m",
+            (Self::Ansi, Highlight::Empty) => "\x1b[2m",
+            (Self::Html, Highlight::PartNumber) => "<span class=\"part\">",
+            (Self::Html, Highlight::IgnoredNumber) => "<span class=\"ignored\">",
+            (Self::Html, Highlight::Gear) => "<span class=\"gear\">",
+            (Self::Html, Highlight::Symbol) => "<span class=\"symbol\">",
+            (Self::Html, Highlight::Empty) => "<span class=\"empty\">",
+        }
+    }
+
+    /// Text after a group of highlighted cells.
+    const fn close(self) -> &'static str {
+        match self {
+            Self::Ansi => "\x1b[0m",
+            Self::Html => "</span>",
+        }
+    }
+}
+
+/// A square window around a position.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Window {
+    /// Center of the window.
+    center: Position,
+    /// Amount of cells on each side of the center.
+    radius: usize,
 }
 
-pub fn second_part() -> u32 {
-    const GEAR: char = '*';
+impl Window {
+    /// Get the rows and columns of the window, cropped to a grid.
+    fn ranges(self, grid: &Grid) -> (Range<usize>, Range<usize>) {
+        let crop = |center: usize, len: usize| {
+            center.saturating_sub(self.radius).min(len)
+                ..center
+                    .saturating_add(self.radius)
+                    .saturating_add(1)
+                    .min(len)
+        };
+        (
+            crop(self.center.row, grid.height()),
+            crop(self.center.column, grid.width()),
+        )
+    }
+}
 
-    // Split the input into lines.
-    let lines: Vec<_> = INPUT.trim().lines().collect();
+/// A number of the schematic.
+#[derive(Debug, PartialEq)]
+struct Number {
+    /// Row of the number.
+    row: usize,
+    /// Columns covered by the digits.
+    columns: Range<usize>,
+    /// Value of the number.
+    value: u32,
+}
 
-    // Find stars
-    lines
-        .iter()
-        .enumerate()
-        .flat_map(|(line, s)| {
-            s.chars().enumerate().filter_map(move |(column, c)| {
-                if c == GEAR {
-                    Some((line, column))
-                } else {
-                    None
-                }
+/// A symbol of the schematic.
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    /// Position of the symbol.
+    position: Position,
+    /// The symbol itself.
+    symbol: char,
+}
+
+/// An engine schematic.
+#[derive(Debug)]
+struct Schematic {
+    /// The raw schematic.
+    grid: Grid,
+    /// The numbers, in reading order.
+    numbers: Vec<Number>,
+    /// The symbols, in reading order.
+    symbols: Vec<Symbol>,
+    /// For each symbol, the indices of the adjacent numbers.
+    adjacent_numbers: Vec<Vec<usize>>,
+    /// For each number, the indices of the adjacent symbols.
+    adjacent_symbols: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    /// Check whether a number is adjacent to at least one symbol.
+    fn is_part_number(&self, number: usize) -> bool {
+        !self.adjacent_symbols[number].is_empty()
+    }
+
+    /// Iterate over the numbers adjacent to a symbol.
+    fn numbers_adjacent_to(&self, symbol: usize) -> impl '_ + Iterator<Item = &Number> {
+        self.adjacent_numbers[symbol]
+            .iter()
+            .map(|number| &self.numbers[*number])
+    }
+
+    /// Iterate over the indices of the symbols of a set whose amount of
+    ///   adjacent numbers satisfies a constraint.
+    fn symbols_matching<'a>(
+        &'a self,
+        symbols: &'a SymbolSet,
+        neighbours: Neighbours,
+    ) -> impl 'a + Iterator<Item = usize> {
+        self.symbols
+            .iter()
+            .zip(&self.adjacent_numbers)
+            .enumerate()
+            .filter(move |(_index, (symbol, numbers))| {
+                symbols.contains(symbol.symbol) && neighbours.accepts(numbers.len())
             })
-        })
-        // Attach surrounding numbers.
-        .map(|(line, column)| {
-            let mut numbers = Vec::new();
-            // Left.
-            if let Some(s) = lines[line][..column]
-                .split(|c: char| !c.is_ascii_digit())
-                .last()
-            {
-                if !s.is_empty() {
-                    numbers.push(s);
-                }
+            .map(|(index, _)| index)
+    }
+
+    /// Combine the numbers adjacent to a symbol, or none on overflow.
+    fn aggregate(&self, symbol: usize, aggregation: Aggregation) -> Option<u64> {
+        let mut values = self
+            .numbers_adjacent_to(symbol)
+            .map(|number| u64::from(number.value));
+        match aggregation {
+            Aggregation::Sum => values.try_fold(0, u64::checked_add),
+            Aggregation::Product => values.try_fold(1, u64::checked_mul),
+        }
+    }
+
+    /// Sum the aggregated numbers of every symbol matching a query, or none
+    ///   on overflow.
+    fn query(
+        &self,
+        symbols: &SymbolSet,
+        neighbours: Neighbours,
+        aggregation: Aggregation,
+    ) -> Option<u64> {
+        self.symbols_matching(symbols, neighbours)
+            .try_fold(0, |total: u64, symbol| {
+                total.checked_add(self.aggregate(symbol, aggregation)?)
+            })
+    }
+
+    /// Check whether a symbol is a gear: a star with exactly 2 adjacent
+    ///   numbers.
+    fn is_gear(&self, symbol: usize) -> bool {
+        self.symbols[symbol].symbol == GEAR && self.adjacent_numbers[symbol].len() == 2
+    }
+
+    /// Render the schematic, highlighting the part numbers, the ignored
+    ///   numbers and the gears.
+    /// Each row is followed by the ratios of its gears.
+    fn render(&self, format: Format, window: Option<Window>) -> String {
+        // Highlight each cell.
+        let mut highlights = vec![Highlight::Empty; self.grid.width() * self.grid.height()];
+        for (index, number) in self.numbers.iter().enumerate() {
+            let highlight = if self.is_part_number(index) {
+                Highlight::PartNumber
+            } else {
+                Highlight::IgnoredNumber
+            };
+            let start = number.row * self.grid.width();
+            highlights[start + number.columns.start..start + number.columns.end].fill(highlight);
+        }
+        for (index, symbol) in self.symbols.iter().enumerate() {
+            let Position { row, column } = symbol.position;
+            highlights[row * self.grid.width() + column] = if self.is_gear(index) {
+                Highlight::Gear
+            } else {
+                Highlight::Symbol
+            };
+        }
+
+        // Get the rendered area.
+        let (rows, columns) = window.map_or_else(
+            || (0..self.grid.height(), 0..self.grid.width()),
+            |window| window.ranges(&self.grid),
+        );
+
+        let mut output = String::new();
+        output.push_str(format.header());
+        for row in rows {
+            // Group the consecutive cells with the same highlight.
+            let mut column = columns.start;
+            while column < columns.end {
+                let highlight = highlights[row * self.grid.width() + column];
+                let end = (column..columns.end)
+                    .find(|c| highlights[row * self.grid.width() + c] != highlight)
+                    .unwrap_or(columns.end);
+                let text: String = self.grid.row(row).unwrap_or_default()[column..end]
+                    .iter()
+                    .collect();
+                output.push_str(format.open(highlight));
+                output.push_str(&format.escape(&text));
+                output.push_str(format.close());
+                column = end;
             }
-            // Right
-            if let Some(s) = lines[line][column + 1..]
-                .split(|c: char| !c.is_ascii_digit())
-                .next()
-            {
-                if !s.is_empty() {
-                    numbers.push(s);
+            // Annotate the gears of the row.
+            for (index, symbol) in self.symbols.iter().enumerate() {
+                if symbol.position.row == row
+                    && columns.contains(&symbol.position.column)
+                    && self.is_gear(index)
+                {
+                    let ratio = self
+                        .aggregate(index, Aggregation::Product)
+                        .expect("The product of 2 numbers fits");
+                    let _ = write!(output, "  {}:{ratio}", symbol.position.column);
                 }
             }
-            // Top and bottom
-            let top = line.checked_sub(1);
-            let bottom = line + 1;
-            for line in top.into_iter().chain(Some(bottom)) {
-                // If the character on the same column is a digit.
-                if lines[line][column..].starts_with(|c: char| c.is_ascii_digit()) {
-                    // Find the start of the number.
-                    let column = lines[line][..column]
-                        .rfind(|c: char| !c.is_ascii_digit())
-                        .map_or(0, |column| column + 1);
-                    // Get the number.
-                    if let Some(number) = lines[line][column..]
-                        .split(|c: char| !c.is_ascii_digit())
-                        .next()
-                    {
-                        numbers.push(number);
+            output.push('\n');
+        }
+        output.push_str(format.footer());
+        output
+    }
+
+    /// Count the symbols by kind and amount of adjacent numbers.
+    fn neighbour_counts(&self) -> BTreeMap<(char, usize), usize> {
+        self.symbols.iter().zip(&self.adjacent_numbers).fold(
+            BTreeMap::new(),
+            |mut counts, (symbol, numbers)| {
+                *counts.entry((symbol.symbol, numbers.len())).or_default() += 1;
+                counts
+            },
+        )
+    }
+}
+
+/// Build a schematic from a grid.
+impl TryFrom<Grid> for Schematic {
+    type Error = InvalidSchematic;
+
+    fn try_from(grid: Grid) -> Result<Self, Self::Error> {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        // Index of the number covering each cell, if any.
+        let mut owners = vec![None; grid.width() * grid.height()];
+
+        for (row, cells) in grid.rows().enumerate() {
+            let mut column = 0;
+            while column < cells.len() {
+                let c = cells[column];
+                if let Some(digit) = c.to_digit(10) {
+                    // Consume the whole number.
+                    let start = column;
+                    let mut value = Some(digit);
+                    column += 1;
+                    while let Some(digit) = cells.get(column).and_then(|c| c.to_digit(10)) {
+                        value = value
+                            .and_then(|value| value.checked_mul(10))
+                            .and_then(|value| value.checked_add(digit));
+                        column += 1;
                     }
+                    let value = value.ok_or(InvalidSchematic::Overflow {
+                        row,
+                        columns: start..column,
+                    })?;
+                    owners[row * grid.width() + start..row * grid.width() + column]
+                        .fill(Some(numbers.len()));
+                    numbers.push(Number {
+                        row,
+                        columns: start..column,
+                        value,
+                    });
                 } else {
-                    // Number after the current column.
-                    if let Some(s) = lines[line][column + 1..]
-                        .split(|c: char| !c.is_ascii_digit())
-                        .next()
-                    {
-                        if !s.is_empty() {
-                            numbers.push(s);
-                        }
-                    }
-                    // Number before the current column.
-                    if let Some(s) = lines[line][..column]
-                        .rsplit(|c: char| !c.is_ascii_digit())
-                        .next()
-                    {
-                        if !s.is_empty() {
-                            numbers.push(s);
-                        }
+                    if is_symbol(c) {
+                        symbols.push(Symbol {
+                            position: Position::new(row, column),
+                            symbol: c,
+                        });
                     }
+                    column += 1;
                 }
             }
-            numbers
-        })
-        // Filter the gears with 2 adjacent numbers.
-        .filter(|numbers| numbers.len() == 2)
-        // Compute gear ratios
-        .map(|numbers| {
-            numbers
-                .into_iter()
-                .filter_map(|n| n.parse::<u32>().ok())
-                .product::<u32>()
+        }
+
+        // Link the symbols with the surrounding numbers.
+        let mut adjacent_symbols = vec![Vec::new(); numbers.len()];
+        let adjacent_numbers = symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| {
+                let mut adjacent: Vec<usize> = grid
+                    .neighbours_8(symbol.position)
+                    .filter_map(|p| owners[p.row * grid.width() + p.column])
+                    .collect();
+                // The same number may touch the symbol on several digits.
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                for number in &adjacent {
+                    adjacent_symbols[*number].push(index);
+                }
+                adjacent
+            })
+            .collect();
+
+        Ok(Self {
+            grid,
+            numbers,
+            symbols,
+            adjacent_numbers,
+            adjacent_symbols,
         })
+    }
+}
+
+/// Parse a schematic.
+impl FromStr for Schematic {
+    type Err = InvalidSchematic;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.parse::<Grid>()?)
+    }
+}
+
+pub fn first_part() -> u64 {
+    // Parse the input.
+    let schematic: Schematic = INPUT.parse().expect("Invalid input");
+
+    // Find numbers.
+    schematic
+        .numbers
+        .iter()
+        .enumerate()
+        // Filter the number which are adjacent to a symbol.
+        .filter(|(index, _number)| schematic.is_part_number(*index))
+        .map(|(_index, number)| u64::from(number.value))
         // Sum.
         .sum()
 }
+
+pub fn second_part() -> u64 {
+    // Parse the input.
+    let schematic: Schematic = INPUT.parse().expect("Invalid input");
+
+    // Sum the ratios of the stars with exactly 2 adjacent numbers.
+    schematic
+        .query(
+            &SymbolSet::OneOf(vec![GEAR]),
+            Neighbours::Exactly(2),
+            Aggregation::Product,
+        )
+        .expect("Overflow")
+}
+
+/// Run a day 3 command.
+///
+/// - `query <symbols> <neighbours> <aggregation>` prints the result of a
+///   query, such as `query * =2 product` for the second part.
+/// - `counts` prints the amount of symbols by kind and amount of adjacent
+///   numbers.
+/// - `render [--html FILE] [ROW COLUMN RADIUS]` prints the highlighted
+///   schematic (or writes it as HTML), optionally cropped around a position.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day3 query <any|SYMBOLS> <=N|>=N> <sum|product>
+       day3 counts
+       day3 render [--html FILE] [ROW COLUMN RADIUS]";
+
+    let schematic: Schematic = INPUT.parse().map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("query") => {
+            let mut next = || args.next().ok_or(USAGE);
+            let symbols = next()?.parse().map_err(|_| USAGE)?;
+            let neighbours = next()?.parse().map_err(|_| USAGE)?;
+            let aggregation = next()?.parse().map_err(|_| USAGE)?;
+            let result = schematic
+                .query(&symbols, neighbours, aggregation)
+                .ok_or("Overflow")?;
+            println!("{result}");
+        }
+        Some("counts") => {
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(["Symbol", "Neighbours", "Count"]);
+            for ((symbol, neighbours), count) in schematic.neighbour_counts() {
+                table.add_row([
+                    symbol.to_string(),
+                    neighbours.to_string(),
+                    count.to_string(),
+                ]);
+            }
+            println!("{table}");
+        }
+        Some("render") => {
+            let mut args = args.peekable();
+            // Get the output file.
+            let html = if args.peek().is_some_and(|arg| arg == "--html") {
+                args.next();
+                Some(args.next().ok_or(USAGE)?)
+            } else {
+                None
+            };
+            // Get the window.
+            let values = args
+                .map(|arg| arg.parse().map_err(|_| USAGE))
+                .collect::<Result<Vec<usize>, _>>()?;
+            let window = match values[..] {
+                [] => None,
+                [row, column, radius] => Some(Window {
+                    center: Position::new(row, column),
+                    radius,
+                }),
+                _ => return Err(USAGE.into()),
+            };
+            // Render.
+            if let Some(path) = html {
+                fs::write(&path, schematic.render(Format::Html, window))
+                    .map_err(|error| format!("{path}: {error}"))?;
+            } else {
+                print!("{}", schematic.render(Format::Ansi, window));
+            }
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Aggregation, Format, InvalidSchematic, Neighbours, Number, Schematic, Symbol, SymbolSet,
+        Window,
+    };
+    use crate::grid::Position;
+
+    const INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn parse() {
+        let schematic: Schematic = INPUT.parse().unwrap();
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(
+            schematic.numbers[1],
+            Number {
+                row: 0,
+                columns: 5..8,
+                value: 114
+            }
+        );
+        assert_eq!(
+            schematic.symbols[0],
+            Symbol {
+                position: Position::new(1, 3),
+                symbol: '*'
+            }
+        );
+        // The first star touches 467 and 35.
+        assert_eq!(schematic.adjacent_numbers[0], [0, 2]);
+        // 114 and 58 are not part numbers.
+        let ignored: Vec<_> = (0..schematic.numbers.len())
+            .filter(|number| !schematic.is_part_number(*number))
+            .map(|number| schematic.numbers[number].value)
+            .collect();
+        assert_eq!(ignored, [114, 58]);
+        // Numbers which do not fit.
+        assert_eq!(
+            "4294967295\n.........*"
+                .parse::<Schematic>()
+                .unwrap()
+                .numbers[0]
+                .value,
+            u32::MAX
+        );
+        assert_eq!(
+            "*..........\n.4294967296".parse::<Schematic>().err(),
+            Some(InvalidSchematic::Overflow {
+                row: 1,
+                columns: 1..11
+            })
+        );
+    }
+
+    #[test]
+    fn query() {
+        let schematic: Schematic = INPUT.parse().unwrap();
+        let stars = SymbolSet::OneOf(vec!['*']);
+        assert_eq!(
+            schematic.query(&stars, Neighbours::Exactly(2), Aggregation::Product),
+            Some(467_835)
+        );
+        assert_eq!(
+            schematic.query(&stars, Neighbours::Exactly(1), Aggregation::Sum),
+            Some(617)
+        );
+        assert_eq!(
+            schematic.query(&SymbolSet::Any, Neighbours::AtLeast(1), Aggregation::Sum),
+            Some(4361)
+        );
+        let schematic: Schematic = "4000000000...........
+..........*4000000000
+4000000000..........."
+            .parse()
+            .unwrap();
+        assert_eq!(
+            schematic.query(&SymbolSet::Any, Neighbours::AtLeast(3), Aggregation::Sum),
+            Some(12_000_000_000)
+        );
+        assert_eq!(
+            schematic.query(
+                &SymbolSet::Any,
+                Neighbours::AtLeast(3),
+                Aggregation::Product
+            ),
+            None
+        );
+        let schematic: Schematic = INPUT.parse().unwrap();
+        assert_eq!(
+            schematic
+                .numbers_adjacent_to(0)
+                .map(|n| n.value)
+                .collect::<Vec<_>>(),
+            [467, 35]
+        );
+        assert_eq!(schematic.neighbour_counts()[&('*', 2)], 2);
+        assert_eq!("*#".parse(), Ok(SymbolSet::OneOf(vec!['*', '#'])));
+        assert_eq!(">=3".parse(), Ok(Neighbours::AtLeast(3)));
+        assert_eq!("product".parse(), Ok(Aggregation::Product));
+        assert!("12".parse::<SymbolSet>().is_err());
+        assert!("2".parse::<Neighbours>().is_err());
+    }
+
+    #[test]
+    fn render() {
+        let schematic: Schematic = INPUT.parse().unwrap();
+        let window = Window {
+            center: Position::new(0, 0),
+            radius: 1,
+        };
+        assert_eq!(
+            schematic.render(Format::Ansi, Some(window)),
+            "\x1b[32m46\x1b[0m\n\x1b[2m..\x1b[0m\n"
+        );
+        let window = Window {
+            center: Position::new(1, 4),
+            radius: 1,
+        };
+        assert_eq!(
+            schematic.render(Format::Ansi, Some(window)),
+            "\x1b[2m..\x1b[0m\x1b[31m1\x1b[0m
+\x1b[1;33m*\x1b[0m\x1b[2m..\x1b[0m  3:16345
+\x1b[32m5\x1b[0m\x1b[2m..\x1b[0m
+"
+        );
+        let window = Window {
+            center: Position::new(0, 0),
+            radius: usize::MAX,
+        };
+        assert_eq!(
+            schematic.render(Format::Ansi, Some(window)),
+            schematic.render(Format::Ansi, None)
+        );
+        let html = schematic.render(Format::Html, None);
+        assert!(html.contains("<span class=\"ignored\">114</span>"));
+        assert!(html
+            .contains("<span class=\"gear\">*</span><span class=\"empty\">....</span>  5:451490"));
+        let schematic: Schematic = "1&<\n>..".parse().unwrap();
+        let html = schematic.render(Format::Html, None);
+        assert!(html.contains(
+            "<span class=\"part\">1</span><span class=\"symbol\">&amp;&lt;</span>\n\
+             <span class=\"symbol\">&gt;</span>"
+        ));
+    }
+}
@@ -1,10 +1,173 @@
-use std::str::FromStr;
+use std::{collections::BTreeSet, fmt, str::FromStr};
 
 const INPUT: &str = include_str!("day4.txt");
 
 /// Invalid input error.
-#[derive(Debug)]
-struct InvalidInput;
+#[derive(Debug, PartialEq)]
+enum InvalidInput {
+    /// The cards are valid but have anomalies.
+    Anomalies(Vec<Anomaly>),
+    /// A card cannot be parsed.
+    Malformed {
+        /// The offending part of the card, the whole card if it is not
+        ///   structured like one.
+        token: String,
+    },
+    /// Other kinds of error.
+    Other,
+}
+
+/// A list of numbers of a card.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum List {
+    /// The winning numbers.
+    Winning,
+    /// The got numbers.
+    Got,
+}
+
+/// Something suspicious in the cards, which changes the amount of matching
+///   numbers or the copies.
+#[derive(Debug, PartialEq)]
+enum Anomaly {
+    /// A card cannot be parsed, so it is skipped.
+    Malformed {
+        /// Line of the card (starting at 1).
+        line: usize,
+        /// The offending part of the card, the whole card if it is not
+        ///   structured like one.
+        token: String,
+    },
+    /// A number appears several times in the same list.
+    Duplicate {
+        /// Line of the card (starting at 1).
+        line: usize,
+        /// ID of the card.
+        card: u32,
+        /// List containing the number.
+        list: List,
+        /// The duplicated number.
+        number: u32,
+        /// Position of the duplicate in the list (starting at 0).
+        position: usize,
+    },
+    /// A card ID does not follow the previous one.
+    OutOfOrder {
+        /// Line of the card (starting at 1).
+        line: usize,
+        /// ID of the card.
+        card: u32,
+        /// The expected ID.
+        expected: u32,
+    },
+    /// A card does not have the same amount of numbers as the first one.
+    CountMismatch {
+        /// Line of the card (starting at 1).
+        line: usize,
+        /// ID of the card.
+        card: u32,
+        /// Amount of winning and got numbers.
+        counts: (usize, usize),
+        /// Amount of winning and got numbers of the first card.
+        expected: (usize, usize),
+    },
+}
+
+/// Describe an anomaly.
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { line, token } => {
+                write!(f, "line {line}: malformed card at {token:?}")
+            }
+            Self::Duplicate {
+                line,
+                card,
+                list,
+                number,
+                position,
+            } => {
+                let list = match list {
+                    List::Winning => "winning",
+                    List::Got => "got",
+                };
+                write!(
+                    f,
+                    "line {line}: card {card} repeats {list} number {number} at position {position}"
+                )
+            }
+            Self::OutOfOrder {
+                line,
+                card,
+                expected,
+            } => write!(f, "line {line}: card {card} should be card {expected}"),
+            Self::CountMismatch {
+                line,
+                card,
+                counts,
+                expected,
+            } => write!(
+                f,
+                "line {line}: card {card} has {}|{} numbers instead of {}|{}",
+                counts.0, counts.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+/// How to handle the anomalies of the cards.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    /// Reject the cards.
+    Strict,
+    /// Accept the cards, reporting the anomalies.
+    Lenient,
+}
+
+/// A set of card numbers.
+#[derive(Debug, PartialEq)]
+enum NumberSet {
+    /// Numbers below 128, one bit per number.
+    Small(u128),
+    /// Any numbers, sorted and without duplicates.
+    Large(Vec<u32>),
+}
+
+impl NumberSet {
+    /// Check whether a number belongs to the set.
+    fn contains(&self, n: u32) -> bool {
+        match self {
+            Self::Small(bits) => n < u128::BITS && bits >> n & 1 == 1,
+            Self::Large(numbers) => numbers.binary_search(&n).is_ok(),
+        }
+    }
+
+    /// Count the numbers belonging to both sets.
+    fn intersection_len(&self, other: &Self) -> usize {
+        match (self, other) {
+            // Fast path: intersect the bits.
+            (Self::Small(a), Self::Small(b)) => (a & b).count_ones() as usize,
+            (Self::Small(bits), _) => (0..u128::BITS)
+                .filter(|n| bits >> n & 1 == 1 && other.contains(*n))
+                .count(),
+            (Self::Large(numbers), _) => numbers.iter().filter(|n| other.contains(**n)).count(),
+        }
+    }
+}
+
+/// Build a set of numbers, using bits if they are all small enough.
+impl FromIterator<u32> for NumberSet {
+    fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
+        let mut numbers: Vec<u32> = iter.into_iter().collect();
+        if numbers.iter().all(|n| *n < u128::BITS) {
+            Self::Small(numbers.into_iter().fold(0, |bits, n| bits | 1 << n))
+        } else {
+            numbers.sort_unstable();
+            numbers.dedup();
+            Self::Large(numbers)
+        }
+    }
+}
 
 /// A game card.
 #[derive(Debug, PartialEq)]
@@ -12,15 +175,17 @@ struct Card {
     /// ID of the card.
     id: u32,
     /// Winning numbers.
-    winning: Vec<u32>,
+    winning: NumberSet,
     /// Got numbers.
-    got: Vec<u32>,
+    got: NumberSet,
 }
 
 impl Card {
     /// Count the amount of matching numbers.
+    ///
+    /// The numbers are sets: a number repeated in a list counts once.
     fn matching(&self) -> usize {
-        self.got.iter().filter(|n| self.winning.contains(n)).count()
+        self.got.intersection_len(&self.winning)
     }
 
     /// Compute the amount of points of a card.
@@ -33,75 +198,315 @@ impl Card {
     }
 }
 
+/// Split a line of the input into the ID, the winning numbers and the got
+///   numbers, or get the malformed part of the line.
+fn split_card(s: &str) -> Result<(u32, Vec<u32>, Vec<u32>), String> {
+    let malformed = str::to_owned;
+    // Strip the prefix.
+    let rest = s.strip_prefix("Card ").ok_or_else(|| malformed(s))?;
+    // Split the ID from the rest.
+    let (id, rest) = rest.split_once(": ").ok_or_else(|| malformed(s))?;
+    let id = id.trim_start().parse().map_err(|_| malformed(id))?;
+    // Split the winning numbers from the got numbers.
+    let (winning, got) = rest.split_once(" | ").ok_or_else(|| malformed(s))?;
+    // Convert winning and got numbers into a list of integers.
+    let numbers = |list: &str| {
+        list.split_ascii_whitespace()
+            .map(|number| number.parse().map_err(|_| malformed(number)))
+            .collect::<Result<_, _>>()
+    };
+    Ok((id, numbers(winning)?, numbers(got)?))
+}
+
 /// Convert a line of the input into a card.
 impl FromStr for Card {
     type Err = InvalidInput;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Strip the prefix.
-        let s = s.strip_prefix("Card ").ok_or(InvalidInput)?;
-        // Split the ID from the rest.
-        let (id, s) = s.split_once(": ").ok_or(InvalidInput)?;
-        let id = id.trim_start().parse().map_err(|_| InvalidInput)?;
-        // Split the winning numbers from the got numbers.
-        let (winning, got) = s.split_once(" | ").ok_or(InvalidInput)?;
-        // Convert winning and got numbers into a list of integers.
-        let winning = winning
-            .split_ascii_whitespace()
-            .map(|s| s.parse().map_err(|_| InvalidInput))
-            .collect::<Result<_, _>>()?;
-        let got = got
-            .split_ascii_whitespace()
-            .map(|s| s.parse().map_err(|_| InvalidInput))
-            .collect::<Result<_, _>>()?;
+        let (id, winning, got) =
+            split_card(s).map_err(|token| InvalidInput::Malformed { token })?;
         // Return the resulting card.
-        Ok(Self { id, winning, got })
+        Ok(Self {
+            id,
+            winning: winning.into_iter().collect(),
+            got: got.into_iter().collect(),
+        })
+    }
+}
+
+/// Find the anomalies of the cards of an input.
+fn validate(s: &str) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    let mut expected_id: u32 = 1;
+    let mut expected_counts = None;
+
+    for (index, s) in s.trim().lines().enumerate() {
+        let line = index + 1;
+        let (card, winning, got) = match split_card(s) {
+            Ok(card) => card,
+            Err(token) => {
+                anomalies.push(Anomaly::Malformed { line, token });
+                // Assume the line was the expected card.
+                expected_id = expected_id.saturating_add(1);
+                continue;
+            }
+        };
+
+        // Check for duplicated numbers.
+        for (list, numbers) in [(List::Winning, &winning), (List::Got, &got)] {
+            for (position, number) in numbers.iter().enumerate() {
+                if numbers[..position].contains(number) {
+                    anomalies.push(Anomaly::Duplicate {
+                        line,
+                        card,
+                        list,
+                        number: *number,
+                        position,
+                    });
+                }
+            }
+        }
+
+        // Check the ID.
+        if card != expected_id {
+            anomalies.push(Anomaly::OutOfOrder {
+                line,
+                card,
+                expected: expected_id,
+            });
+        }
+        expected_id = card.saturating_add(1);
+
+        // Check the amounts of numbers.
+        let counts = (winning.len(), got.len());
+        let expected = *expected_counts.get_or_insert(counts);
+        if counts != expected {
+            anomalies.push(Anomaly::CountMismatch {
+                line,
+                card,
+                counts,
+                expected,
+            });
+        }
     }
+
+    anomalies
 }
 
 pub fn first_part() -> u32 {
     // Parse the input.
-    INPUT
-        .trim()
-        .lines()
-        .map(|s| s.parse::<Card>().expect("Invalid input"))
+    let (cards, _anomalies) = parse_cards(INPUT, Mode::Strict).expect("Invalid input");
+    cards
+        .into_iter()
         // Compute the points of each card.
         .map(|card| card.points())
         // Sum it.
         .sum()
 }
 
-pub fn second_part() -> u32 {
-    // Count the amount of cards
-    let count = INPUT.trim().lines().count();
+/// What to do with the copies won past the end of the table.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Overflow {
+    /// Ignore them.
+    Clamp,
+    /// Reject them.
+    Error,
+    /// Win copies of the cards from the top of the table.
+    Wrap,
+}
 
-    // Create a vector of cards count.
-    let mut counts = vec![1; count];
+/// Parse an overflow policy: `clamp`, `error` or `wrap`.
+impl FromStr for Overflow {
+    type Err = InvalidInput;
 
-    // Parse the input.
-    let cards = INPUT
-        .trim()
-        .lines()
-        .map(|s| s.parse::<Card>().expect("Invalid input"));
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clamp" => Ok(Self::Clamp),
+            "error" => Ok(Self::Error),
+            "wrap" => Ok(Self::Wrap),
+            _ => Err(InvalidInput::Other),
+        }
+    }
+}
+
+/// Error while running a cascade.
+#[derive(Debug, PartialEq)]
+enum CascadeError {
+    /// A card wins copies past the end of the table.
+    Overflow {
+        /// ID of the card.
+        card: u32,
+    },
+    /// A card (indirectly) wins copies of itself, so the cascade never ends.
+    Cycle {
+        /// ID of the card.
+        card: u32,
+    },
+}
 
-    // Iterate from the top-most card to the bottom.
-    for (index, card) in cards.enumerate() {
-        // Get the matching count.
-        let matching = card.matching();
+/// The copies won by a table of cards.
+#[derive(Debug)]
+struct Cascade {
+    /// ID of each card.
+    ids: Vec<u32>,
+    /// Amount of instances of each card, including the original.
+    copies: Vec<u32>,
+    /// For each card, the indices of the cards it wins a copy of.
+    /// Each instance of the card wins one copy of each of them.
+    won: Vec<Vec<usize>>,
+    /// Indices of the cards, in processing order.
+    order: Vec<usize>,
+}
+
+impl Cascade {
+    /// Run the cascade of a table of cards.
+    fn new(cards: &[Card], overflow: Overflow) -> Result<Self, CascadeError> {
+        let len = cards.len();
+
+        // Find the cards won by each card.
+        let won = cards
+            .iter()
+            .enumerate()
+            .map(|(index, card)| {
+                (index + 1..=index + card.matching())
+                    .filter_map(|won| match overflow {
+                        _ if won < len => Some(Ok(won)),
+                        Overflow::Clamp => None,
+                        Overflow::Error => Some(Err(CascadeError::Overflow { card: card.id })),
+                        Overflow::Wrap => Some(Ok(won % len)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Count how many cards win copies of each card.
+        let mut pending = vec![0; len];
+        won.iter().flatten().for_each(|won| pending[*won] += 1);
+
+        // Process a card once all the cards winning copies of it are processed,
+        //   top-most first.
+        let mut ready: BTreeSet<_> = (0..len).filter(|index| pending[*index] == 0).collect();
+        let mut copies = vec![1; len];
+        let mut order = Vec::with_capacity(len);
+        while let Some(index) = ready.pop_first() {
+            order.push(index);
+            for won in &won[index] {
+                copies[*won] += copies[index];
+                pending[*won] -= 1;
+                if pending[*won] == 0 {
+                    ready.insert(*won);
+                }
+            }
+        }
 
-        // Increase the counts of the following next cards.
-        for i in 1..=matching {
-            counts[index + i] += counts[index];
+        // The remaining cards are part of a cycle.
+        if let Some(index) = (0..len).find(|index| pending[*index] > 0) {
+            return Err(CascadeError::Cycle {
+                card: cards[index].id,
+            });
         }
+
+        Ok(Self {
+            ids: cards.iter().map(|card| card.id).collect(),
+            copies,
+            won,
+            order,
+        })
+    }
+}
+
+/// Trace the cascade, one card per line in processing order.
+impl fmt::Display for Cascade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for index in &self.order {
+            let copies = self.copies[*index];
+            write!(
+                f,
+                "Card {}: {copies} {}, ",
+                self.ids[*index],
+                if copies == 1 { "copy" } else { "copies" }
+            )?;
+            if self.won[*index].is_empty() {
+                writeln!(f, "wins nothing")?;
+            } else {
+                let won: Vec<_> = self.won[*index]
+                    .iter()
+                    .map(|won| self.ids[*won].to_string())
+                    .collect();
+                writeln!(f, "each wins cards {}", won.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the cards of an input, along with their anomalies.
+/// In strict mode, the anomalies are rejected, in lenient mode the malformed
+///   cards are skipped.
+fn parse_cards(s: &str, mode: Mode) -> Result<(Vec<Card>, Vec<Anomaly>), InvalidInput> {
+    let anomalies = validate(s);
+    if mode == Mode::Strict && !anomalies.is_empty() {
+        return Err(InvalidInput::Anomalies(anomalies));
     }
+    let cards = s
+        .trim()
+        .lines()
+        .filter_map(|line| line.parse().ok())
+        .collect();
+    Ok((cards, anomalies))
+}
+
+pub fn second_part() -> u32 {
+    // Parse the input.
+    let (cards, _anomalies) = parse_cards(INPUT, Mode::Strict).expect("Invalid input");
+
+    // Run the cascade.
+    let cascade = Cascade::new(&cards, Overflow::Error).expect("Invalid input");
 
     // Total count.
-    counts.into_iter().sum::<u32>()
+    cascade.copies.into_iter().sum::<u32>()
+}
+
+/// Run a day 4 command.
+///
+/// - `check` prints the anomalies of the cards.
+/// - `trace [clamp|error|wrap]` prints the cascade of copies, using the given
+///   policy for the copies won past the end of the table (`error` by default).
+///
+/// The anomalies of the cards are reported as warnings.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day4 check
+       day4 trace [clamp|error|wrap]";
+
+    let (cards, anomalies) = parse_cards(INPUT, Mode::Lenient).map_err(|_| "Invalid input")?;
+    for anomaly in &anomalies {
+        eprintln!("Warning: {anomaly}");
+    }
+    match args.next().as_deref() {
+        Some("check") => {
+            println!("{} anomalies", anomalies.len());
+            Ok(())
+        }
+        Some("trace") => {
+            let overflow = args
+                .next()
+                .map_or(Ok(Overflow::Error), |arg| arg.parse())
+                .map_err(|_| USAGE)?;
+            let cascade = Cascade::new(&cards, overflow).map_err(|error| format!("{error:?}"))?;
+            print!("{cascade}");
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Card;
+    use super::{
+        parse_cards, Anomaly, Card, Cascade, CascadeError, InvalidInput, List, Mode, NumberSet,
+        Overflow,
+    };
+    use std::{hint::black_box, time::Instant};
 
     #[test]
     fn test() {
@@ -118,48 +523,48 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
             cards.next(),
             Some(Card {
                 id: 1,
-                winning: vec![41, 48, 83, 86, 17],
-                got: vec![83, 86, 6, 31, 17, 9, 48, 53]
+                winning: NumberSet::from_iter([41, 48, 83, 86, 17]),
+                got: NumberSet::from_iter([83, 86, 6, 31, 17, 9, 48, 53])
             })
         );
         assert_eq!(
             cards.next(),
             Some(Card {
                 id: 2,
-                winning: vec![13, 32, 20, 16, 61],
-                got: vec![61, 30, 68, 82, 17, 32, 24, 19]
+                winning: NumberSet::from_iter([13, 32, 20, 16, 61]),
+                got: NumberSet::from_iter([61, 30, 68, 82, 17, 32, 24, 19])
             })
         );
         assert_eq!(
             cards.next(),
             Some(Card {
                 id: 3,
-                winning: vec![1, 21, 53, 59, 44],
-                got: vec![69, 82, 63, 72, 16, 21, 14, 1]
+                winning: NumberSet::from_iter([1, 21, 53, 59, 44]),
+                got: NumberSet::from_iter([69, 82, 63, 72, 16, 21, 14, 1])
             })
         );
         assert_eq!(
             cards.next(),
             Some(Card {
                 id: 4,
-                winning: vec![41, 92, 73, 84, 69],
-                got: vec![59, 84, 76, 51, 58, 5, 54, 83]
+                winning: NumberSet::from_iter([41, 92, 73, 84, 69]),
+                got: NumberSet::from_iter([59, 84, 76, 51, 58, 5, 54, 83])
             })
         );
         assert_eq!(
             cards.next(),
             Some(Card {
                 id: 5,
-                winning: vec![87, 83, 26, 28, 32],
-                got: vec![88, 30, 70, 12, 93, 22, 82, 36]
+                winning: NumberSet::from_iter([87, 83, 26, 28, 32]),
+                got: NumberSet::from_iter([88, 30, 70, 12, 93, 22, 82, 36])
             })
         );
         assert_eq!(
             cards.next(),
             Some(Card {
                 id: 6,
-                winning: vec![31, 18, 13, 56, 72],
-                got: vec![74, 77, 10, 23, 35, 67, 36, 11]
+                winning: NumberSet::from_iter([31, 18, 13, 56, 72]),
+                got: NumberSet::from_iter([74, 77, 10, 23, 35, 67, 36, 11])
             })
         );
         assert_eq!(cards.next(), None);
@@ -168,5 +573,189 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let cards = INPUT.lines().map(|s| s.parse::<Card>().unwrap());
         let points: Vec<_> = cards.map(|card| card.points()).collect();
         assert_eq!(points, [8, 2, 2, 1, 0, 0]);
+
+        // Check the cascade of copies.
+        let cards = parse_cards(INPUT, Mode::Strict).unwrap().0;
+        let cascade = Cascade::new(&cards, Overflow::Error).unwrap();
+        assert_eq!(cascade.copies, [1, 2, 4, 8, 14, 1]);
+        assert_eq!(cascade.won[0], [1, 2, 3, 4]);
+        assert_eq!(
+            cascade.to_string().lines().nth(2),
+            Some("Card 3: 4 copies, each wins cards 4, 5")
+        );
+    }
+
+    #[test]
+    fn cascade_overflow() {
+        const INPUT: &str = "Card 1: 1 2 | 1 3
+Card 2: 4 5 | 4 5";
+        let cards = parse_cards(INPUT, Mode::Strict).unwrap().0;
+        assert_eq!(
+            Cascade::new(&cards, Overflow::Error).unwrap_err(),
+            CascadeError::Overflow { card: 2 }
+        );
+        assert_eq!(
+            Cascade::new(&cards, Overflow::Clamp).unwrap().copies,
+            [1, 2]
+        );
+        // Card 1 wins a copy of card 2, which wins copies of cards 1 and 2.
+        assert_eq!(
+            Cascade::new(&cards, Overflow::Wrap).unwrap_err(),
+            CascadeError::Cycle { card: 1 }
+        );
+    }
+
+    #[test]
+    fn cascade_wrap() {
+        const INPUT: &str = "Card 1: 1 | 2
+Card 2: 4 | 4";
+        let cards = parse_cards(INPUT, Mode::Strict).unwrap().0;
+        let cascade = Cascade::new(&cards, Overflow::Wrap).unwrap();
+        assert_eq!(cascade.copies, [2, 1]);
+        assert_eq!(cascade.order, [1, 0]);
+    }
+
+    #[test]
+    fn number_set() {
+        let small = NumberSet::from_iter([3, 1, 4, 1, 5]);
+        let large = NumberSet::from_iter([1, 5, 9, 200, 200]);
+        assert_eq!(small, NumberSet::Small(0b11_1010));
+        assert_eq!(large, NumberSet::Large(vec![1, 5, 9, 200]));
+        assert!(large.contains(200));
+        assert!(!small.contains(200));
+        assert_eq!(small.intersection_len(&large), 2);
+        assert_eq!(large.intersection_len(&small), 2);
+        assert_eq!(large.intersection_len(&large), 4);
+        let card: Card = "Card 1: 5 | 5 5".parse().unwrap();
+        assert_eq!(card.matching(), 1);
+    }
+
+    #[test]
+    fn validate() {
+        const INPUT: &str = "Card 1: 1 2 1 | 3 4
+Card 3: 5 6 | 7 7 8
+Card 2: 9 10 | 11 12";
+        let anomalies = vec![
+            Anomaly::Duplicate {
+                line: 1,
+                card: 1,
+                list: List::Winning,
+                number: 1,
+                position: 2,
+            },
+            Anomaly::Duplicate {
+                line: 2,
+                card: 3,
+                list: List::Got,
+                number: 7,
+                position: 1,
+            },
+            Anomaly::OutOfOrder {
+                line: 2,
+                card: 3,
+                expected: 2,
+            },
+            Anomaly::CountMismatch {
+                line: 2,
+                card: 3,
+                counts: (2, 3),
+                expected: (3, 2),
+            },
+            Anomaly::OutOfOrder {
+                line: 3,
+                card: 2,
+                expected: 4,
+            },
+            Anomaly::CountMismatch {
+                line: 3,
+                card: 2,
+                counts: (2, 2),
+                expected: (3, 2),
+            },
+        ];
+        let (cards, lenient) = parse_cards(INPUT, Mode::Lenient).unwrap();
+        assert_eq!(cards.len(), 3);
+        assert_eq!(lenient, anomalies);
+        assert_eq!(
+            parse_cards(INPUT, Mode::Strict).unwrap_err(),
+            InvalidInput::Anomalies(anomalies)
+        );
+        assert_eq!(
+            lenient[1].to_string(),
+            "line 2: card 3 repeats got number 7 at position 1"
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        // Malformed cards are skipped.
+        const MALFORMED: &str = "Card 1: 1 2 | 3 4
+Card 2: 5 x | 7 8
+Card 3 6 | 7
+Card 4: 9 10 | 11 12";
+        let (cards, anomalies) = parse_cards(MALFORMED, Mode::Lenient).unwrap();
+        assert_eq!(cards.iter().map(|card| card.id).collect::<Vec<_>>(), [1, 4]);
+        assert_eq!(
+            anomalies,
+            [
+                Anomaly::Malformed {
+                    line: 2,
+                    token: "x".into()
+                },
+                Anomaly::Malformed {
+                    line: 3,
+                    token: "Card 3 6 | 7".into()
+                },
+            ]
+        );
+        assert_eq!(anomalies[0].to_string(), "line 2: malformed card at \"x\"");
+        assert!(parse_cards(MALFORMED, Mode::Strict).is_err());
+        assert_eq!(
+            "Card x: 1 | 2".parse::<Card>().err(),
+            Some(InvalidInput::Malformed { token: "x".into() })
+        );
+    }
+
+    /// Compare the bits against the former list of numbers.
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_matching() {
+        const ROUNDS: u32 = 1000;
+
+        let lines: Vec<_> = super::INPUT.trim().lines().collect();
+        let lists: Vec<(Vec<u32>, Vec<u32>)> = lines
+            .iter()
+            .map(|line| {
+                let (winning, got) = line.split_once(": ").unwrap().1.split_once(" | ").unwrap();
+                let parse = |s: &str| {
+                    s.split_ascii_whitespace()
+                        .map(|n| n.parse().unwrap())
+                        .collect()
+                };
+                (parse(winning), parse(got))
+            })
+            .collect();
+        let cards: Vec<Card> = lines.iter().map(|s| s.parse().unwrap()).collect();
+
+        let start = Instant::now();
+        let lists_total: usize = (0..ROUNDS)
+            .map(|_| {
+                black_box(&lists)
+                    .iter()
+                    .map(|(winning, got)| got.iter().filter(|n| winning.contains(n)).count())
+                    .sum::<usize>()
+            })
+            .sum();
+        let lists_time = start.elapsed();
+
+        let start = Instant::now();
+        let bits_total: usize = (0..ROUNDS)
+            .map(|_| black_box(&cards).iter().map(Card::matching).sum::<usize>())
+            .sum();
+        let bits_time = start.elapsed();
+
+        assert_eq!(lists_total, bits_total);
+        println!("lists: {lists_time:?}, bits: {bits_time:?}");
     }
 }
@@ -1,4 +1,12 @@
-use std::{num::ParseIntError, ops::RangeInclusive, str::FromStr};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    num::ParseIntError,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use crate::interval::{IntervalSet, Overflow, Piece, PiecewiseMap, Value};
 
 const INPUT: &str = include_str!("day5.txt");
 
@@ -7,6 +15,10 @@ const INPUT: &str = include_str!("day5.txt");
 enum InvalidInput {
     /// Integer convertion error.
     ParseInt(ParseIntError),
+    /// A range does not fit in the value type.
+    Overflow,
+    /// A map has a zero length.
+    ZeroLength,
     /// Other kinds of error.
     Other,
 }
@@ -18,205 +30,217 @@ impl From<ParseIntError> for InvalidInput {
     }
 }
 
+/// Encapsulate an `Overflow` into an `InvalidInput`.
+impl From<Overflow> for InvalidInput {
+    fn from(_: Overflow) -> Self {
+        Self::Overflow
+    }
+}
+
 /// A mapping.
 #[derive(Debug, PartialEq)]
-struct Map {
+struct Map<T> {
     /// Destination range.
-    destination: RangeInclusive<u32>,
+    destination: RangeInclusive<T>,
     /// Source range.
-    source: RangeInclusive<u32>,
+    source: RangeInclusive<T>,
 }
 
-impl Map {
-    /// Try to map a source into a destination.
-    fn map(&self, source: u32) -> Option<u32> {
-        if self.source.contains(&source) {
-            Some(source - self.source.start() + self.destination.start())
-        } else {
-            None
+/// Convert a mapping into a piece of piecewise map.
+impl<T: Copy> From<&Map<T>> for Piece<T> {
+    fn from(map: &Map<T>) -> Self {
+        Self {
+            source: map.source.clone(),
+            destination: *map.destination.start(),
         }
     }
-
-    /// Try to map a range of sources into a range of destinations.
-    /// Also return is was not mapped.
-    fn map_range(
-        &self,
-        source: RangeInclusive<u32>,
-    ) -> (Option<RangeInclusive<u32>>, Vec<RangeInclusive<u32>>) {
-        // Reject what is before the source range.
-        let before = if source.start() < self.source.start() {
-            let end = (*source.end()).min(self.source.start() - 1);
-            Some(*source.start()..=end)
-        } else {
-            None
-        };
-
-        // Reject what is after the source range.
-        let after = if self.source.end() < source.end() {
-            let start = (self.source.end() + 1).max(*source.start());
-            Some(start..=*source.end())
-        } else {
-            None
-        };
-
-        // Get intersection.
-        let start = (*source.start()).max(*self.source.start());
-        let end = (*source.end()).min(*self.source.end());
-        // Map to destination.
-        let destination = if start <= end {
-            let start = start - self.source.start() + self.destination.start();
-            let end = end - self.source.start() + self.destination.start();
-            Some(start..=end)
-        } else {
-            None
-        };
-
-        let rejected = [before, after].into_iter().flatten().collect();
-
-        (destination, rejected)
-    }
 }
 
-impl FromStr for Map {
+impl<T: Value> FromStr for Map<T> {
     type Err = InvalidInput;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Split the string.
         let mut parts = s.split_ascii_whitespace();
         // Convert the values.
-        let destination_start = parts.next().ok_or(InvalidInput::Other)?.parse()?;
-        let source_start = parts.next().ok_or(InvalidInput::Other)?.parse()?;
-        let range_length: u32 = parts.next().ok_or(InvalidInput::Other)?.parse()?;
+        let destination_start: T = parts.next().ok_or(InvalidInput::Other)?.parse()?;
+        let source_start: T = parts.next().ok_or(InvalidInput::Other)?.parse()?;
+        let range_length: T = parts.next().ok_or(InvalidInput::Other)?.parse()?;
         // Check for additional values.
-        if parts.next().is_none() && range_length > 0 {
-            let r = range_length - 1;
-            Ok(Self {
-                destination: destination_start..=destination_start + r,
-                source: source_start..=source_start + r,
-            })
-        } else {
-            Err(InvalidInput::Other)
+        if parts.next().is_some() {
+            return Err(InvalidInput::Other);
         }
+        // Compute the ends.
+        let r = range_length
+            .checked_sub(T::ONE)
+            .ok_or(InvalidInput::ZeroLength)?;
+        let destination_end = destination_start.checked_add(r).ok_or(Overflow)?;
+        let source_end = source_start.checked_add(r).ok_or(Overflow)?;
+        Ok(Self {
+            destination: destination_start..=destination_end,
+            source: source_start..=source_end,
+        })
     }
 }
 
 /// Collection of maps.
 #[derive(Debug, PartialEq)]
-struct Maps(Vec<Map>);
+struct Maps<T>(Vec<Map<T>>);
 
-impl Maps {
-    /// Map a range of sources into a collecton of destination ranges.
-    fn map(&self, source: RangeInclusive<u32>) -> Vec<RangeInclusive<u32>> {
-        let mut destination = Vec::new();
+impl<T: Value> Maps<T> {
+    /// Convert the maps into a piecewise map.
+    /// When several maps match a source, the first one wins.
+    fn piecewise(&self) -> Result<PiecewiseMap<T>, Overflow> {
+        PiecewiseMap::new(self.0.iter().map(Piece::from))
+    }
+}
 
-        let source = self.0.iter().fold(vec![source], |source, map| {
-            source
-                .into_iter()
-                .flat_map(|s| {
-                    let (d, r) = map.map_range(s);
-                    destination.extend(d);
-                    r
-                })
-                .collect()
-        });
+/// A section of the almanac: the maps from a category to another one.
+#[derive(Debug, PartialEq)]
+struct Section<T> {
+    /// Source category.
+    source: String,
+    /// Destination category.
+    destination: String,
+    /// The maps.
+    maps: Maps<T>,
+}
+
+/// Error while querying the almanac.
+#[derive(Debug, PartialEq)]
+enum QueryError {
+    /// No chain of sections leads from a category to the other one.
+    NoPath,
+    /// A computation does not fit in the value type.
+    Overflow,
+}
 
-        destination.into_iter().chain(source).collect()
+/// Encapsulate an `Overflow` into a `QueryError`.
+impl From<Overflow> for QueryError {
+    fn from(_: Overflow) -> Self {
+        Self::Overflow
     }
 }
 
 /// The input.
-struct Input {
+struct Input<T> {
     /// List of seeds.
-    seeds: Vec<u32>,
-    /// Seed to soil maps.
-    seed_to_soil: Maps,
-    /// Soil to fertilizer maps.
-    soil_to_fertilizer: Maps,
-    /// Fertilizer to water maps.
-    fertilizer_to_water: Maps,
-    /// Water to light maps.
-    water_to_light: Maps,
-    /// Ligth to temperature maps.
-    light_to_temperature: Maps,
-    /// Temperature to humidity maps.
-    temperature_to_humidity: Maps,
-    /// Humidity to location maps.
-    humidity_to_location: Maps,
+    seeds: Vec<T>,
+    /// The sections, in input order.
+    sections: Vec<Section<T>>,
 }
 
-impl Input {
-    /// Map a seed to a location.
-    fn seed_to_location(&self, seed: u32) -> u32 {
-        // Fold the maps.
-        [
-            &self.seed_to_soil,
-            &self.soil_to_fertilizer,
-            &self.fertilizer_to_water,
-            &self.water_to_light,
-            &self.light_to_temperature,
-            &self.temperature_to_humidity,
-            &self.humidity_to_location,
-        ]
-        .into_iter()
-        .fold(seed, |source, maps| {
-            // Find a map matching a source.
-            maps.0
-                .iter()
-                .find_map(|map| map.map(source))
-                .unwrap_or(source)
-        })
+impl<T: Value> Input<T> {
+    /// Find the shortest chain of sections from a category to another one.
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&Section<T>>, QueryError> {
+        // Breadth-first search, remembering the section reaching each category.
+        let mut reached: HashMap<&str, Option<usize>> = HashMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                // Walk back to the first category.
+                let mut chain = Vec::new();
+                let mut category = to;
+                while let Some(index) = reached[category] {
+                    chain.push(&self.sections[index]);
+                    category = &self.sections[index].source;
+                }
+                chain.reverse();
+                return Ok(chain);
+            }
+            for (index, section) in self.sections.iter().enumerate() {
+                if section.source == category && !reached.contains_key(&*section.destination) {
+                    reached.insert(&section.destination, Some(index));
+                    queue.push_back(&section.destination);
+                }
+            }
+        }
+
+        Err(QueryError::NoPath)
     }
 
-    fn seeds_to_locations(&self, seeds: RangeInclusive<u32>) -> Vec<RangeInclusive<u32>> {
-        // Fold the maps.
-        [
-            &self.seed_to_soil,
-            &self.soil_to_fertilizer,
-            &self.fertilizer_to_water,
-            &self.water_to_light,
-            &self.light_to_temperature,
-            &self.temperature_to_humidity,
-            &self.humidity_to_location,
-        ]
-        .into_iter()
-        .fold(vec![seeds], |sources, maps| {
-            // Convert a set of sources into a set of locations.
-            sources
-                .into_iter()
-                .flat_map(|sources| maps.map(sources))
-                .collect()
-        })
+    /// Compose the maps from a category to another one into a single map.
+    fn map_between(&self, from: &str, to: &str) -> Result<PiecewiseMap<T>, QueryError> {
+        self.chain(from, to)?
+            .into_iter()
+            .try_fold(PiecewiseMap::new([])?, |composed, section| {
+                composed.then(&section.maps.piecewise()?)
+            })
+            .map_err(QueryError::from)
+    }
+
+    /// Find the values of a category ending at a set of values of another
+    ///   category.
+    fn preimage(
+        &self,
+        from: &str,
+        to: &str,
+        values: &IntervalSet<T>,
+    ) -> Result<IntervalSet<T>, QueryError> {
+        Ok(self.map_between(from, to)?.preimage(values)?)
+    }
+
+    /// Follow a value through the categories, from a category to another one.
+    fn path<'a>(
+        &'a self,
+        from: &'a str,
+        to: &str,
+        value: T,
+    ) -> Result<Vec<(&'a str, T)>, QueryError> {
+        let mut path = vec![(from, value)];
+        for section in self.chain(from, to)? {
+            let value = section.maps.piecewise()?.map(path[path.len() - 1].1)?;
+            path.push((&section.destination, value));
+        }
+        Ok(path)
+    }
+
+    /// Find the lowest value of a category reachable from a set of values of
+    ///   another category, along with the path of the lowest value leading to
+    ///   it.
+    fn lowest_path<'a>(
+        &'a self,
+        from: &'a str,
+        to: &str,
+        values: &IntervalSet<T>,
+    ) -> Result<Option<Vec<(&'a str, T)>>, QueryError> {
+        let map = self.map_between(from, to)?;
+        let Some(lowest) = map.map_set(values)?.min() else {
+            return Ok(None);
+        };
+        // Find back the values leading to the lowest one.
+        let value = map
+            .preimage(&IntervalSet::from(lowest..=lowest))?
+            .intersection(values)
+            .min()
+            .expect("The lowest value is the image of one of the values");
+        self.path(from, to, value).map(Some)
+    }
+
+    /// Read the seeds as pairs of start and length.
+    fn seed_ranges(&self) -> Result<IntervalSet<T>, Overflow> {
+        self.seeds
+            .chunks_exact(2)
+            .filter_map(|chunk| {
+                // Skip the empty ranges.
+                let r = chunk[1].checked_sub(T::ONE)?;
+                Some(
+                    chunk[0]
+                        .checked_add(r)
+                        .map(|end| chunk[0]..=end)
+                        .ok_or(Overflow),
+                )
+            })
+            .collect()
     }
 }
 
 /// Input parser.
-impl FromStr for Input {
+impl<T: Value> FromStr for Input<T> {
     type Err = InvalidInput;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        /// Parse a list of maps.
-        fn parse_maps<'a, I>(lines: &mut I, name: &str) -> Result<Maps, InvalidInput>
-        where
-            I: 'a + Iterator<Item = &'a str>,
-        {
-            // Get the header.
-            let line = lines.next().ok_or(InvalidInput::Other)?;
-            // Check the header.
-            let s = line
-                .strip_prefix(name)
-                .and_then(|s| s.strip_prefix(" map:"))
-                .ok_or(InvalidInput::Other)?;
-            if !s.is_empty() {
-                return Err(InvalidInput::Other);
-            }
-            // Parse maps until the empty line.
-            lines
-                .take_while(|s| !s.is_empty())
-                .map(str::parse)
-                .collect::<Result<_, _>>()
-                .map(Maps)
-        }
-
         // Split into lines.
         let mut lines = s.trim_end().lines();
 
@@ -225,7 +249,7 @@ impl FromStr for Input {
             .next()
             .and_then(|s| s.strip_prefix("seeds: "))
             .ok_or(InvalidInput::Other)?;
-        let seeds: Vec<u32> = line
+        let seeds: Vec<T> = line
             .split_ascii_whitespace()
             .map(str::parse)
             .collect::<Result<_, _>>()?;
@@ -233,71 +257,318 @@ impl FromStr for Input {
             return Err(InvalidInput::Other);
         }
 
-        // Parse maps.
-        let seed_to_soil = parse_maps(&mut lines, "seed-to-soil")?;
-        let soil_to_fertilizer = parse_maps(&mut lines, "soil-to-fertilizer")?;
-        let fertilizer_to_water = parse_maps(&mut lines, "fertilizer-to-water")?;
-        let water_to_light = parse_maps(&mut lines, "water-to-light")?;
-        let light_to_temperature = parse_maps(&mut lines, "light-to-temperature")?;
-        let temperature_to_humidity = parse_maps(&mut lines, "temperature-to-humidity")?;
-        let humidity_to_location = parse_maps(&mut lines, "humidity-to-location")?;
-
-        // Check end-of-file.
-        if lines.next().is_some() {
-            return Err(InvalidInput::Other);
+        // Parse the sections until end-of-file.
+        let mut sections = Vec::new();
+        while let Some(header) = lines.next() {
+            // Parse the header.
+            let (source, destination) = header
+                .strip_suffix(" map:")
+                .and_then(|s| s.split_once("-to-"))
+                .filter(|(source, destination)| !source.is_empty() && !destination.is_empty())
+                .ok_or(InvalidInput::Other)?;
+            // Parse maps until the empty line.
+            let maps = lines
+                .by_ref()
+                .take_while(|s| !s.is_empty())
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map(Maps)?;
+            sections.push(Section {
+                source: source.into(),
+                destination: destination.into(),
+                maps,
+            });
         }
 
-        Ok(Self {
-            seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
-        })
+        Ok(Self { seeds, sections })
     }
 }
 
-pub fn first_part() -> u32 {
+pub fn first_part() -> u64 {
     // Parse the input.
-    let input: Input = INPUT.parse().expect("Invalid input");
+    let input: Input<u64> = INPUT.parse().expect("Invalid input");
+    // Compose the maps.
+    let map = input
+        .map_between("seed", "location")
+        .expect("Invalid input");
     // Map each seed into a location.
     input
         .seeds
         .iter()
-        .map(|seed| input.seed_to_location(*seed))
+        .map(|seed| map.map(*seed).expect("Overflow"))
         // Find the minimum value.
         .min()
         .expect("Empty seed list")
 }
 
-pub fn second_part() -> u32 {
+pub fn second_part() -> u64 {
     // Parse the input.
-    let input: Input = INPUT.parse().expect("Invalid input");
+    let input: Input<u64> = INPUT.parse().expect("Invalid input");
+    // Compose the maps.
+    let map = input
+        .map_between("seed", "location")
+        .expect("Invalid input");
     // Convert the seeds to range of seeds.
-    input
-        .seeds
-        .chunks_exact(2)
-        .map(|chunk| {
-            let start = chunk[0];
-            let end = start + chunk[1] - 1;
-            start..=end
-        })
-        // Convert seeds to locations.
-        .flat_map(|seeds| input.seeds_to_locations(seeds))
+    let seeds = input.seed_ranges().expect("Overflow");
+    // Convert seeds to locations.
+    map.map_set(&seeds)
+        .expect("Overflow")
         // Get the minimum location
-        .map(|range| *range.start())
         .min()
         .expect("Empty location list")
 }
 
+/// A suspicious part of an almanac section.
+#[derive(Debug, PartialEq)]
+enum Lint<T> {
+    /// Two maps have overlapping source ranges, so the first one wins.
+    OverlappingSources {
+        /// Header of the section.
+        section: String,
+        /// Lines of the maps (starting at 1).
+        lines: (usize, usize),
+    },
+    /// Two maps have overlapping destination ranges.
+    OverlappingDestinations {
+        /// Header of the section.
+        section: String,
+        /// Lines of the maps (starting at 1).
+        lines: (usize, usize),
+    },
+    /// Sources between two maps are not mapped, so they are left unchanged.
+    IdentityGap {
+        /// Header of the section.
+        section: String,
+        /// The unmapped sources.
+        range: RangeInclusive<T>,
+        /// Lines of the maps before and after the gap (starting at 1).
+        lines: (usize, usize),
+    },
+    /// A map has a zero length.
+    ZeroLength {
+        /// Header of the section.
+        section: String,
+        /// Line of the map (starting at 1).
+        line: usize,
+    },
+}
+
+/// Describe a lint.
+impl<T: fmt::Display> fmt::Display for Lint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OverlappingSources { section, lines } => write!(
+                f,
+                "{section}: lines {} and {} have overlapping sources",
+                lines.0, lines.1
+            ),
+            Self::OverlappingDestinations { section, lines } => write!(
+                f,
+                "{section}: lines {} and {} have overlapping destinations",
+                lines.0, lines.1
+            ),
+            Self::IdentityGap {
+                section,
+                range,
+                lines,
+            } => write!(
+                f,
+                "{section}: sources {}..={} between lines {} and {} are not mapped",
+                range.start(),
+                range.end(),
+                lines.0,
+                lines.1
+            ),
+            Self::ZeroLength { section, line } => {
+                write!(f, "{section}: line {line} has a zero length")
+            }
+        }
+    }
+}
+
+/// Check whether two ranges overlap.
+fn overlap<T: Ord>(a: &RangeInclusive<T>, b: &RangeInclusive<T>) -> bool {
+    a.start().max(b.start()) <= a.end().min(b.end())
+}
+
+/// The maps of a section, along with their lines.
+type NumberedMaps<T> = Vec<(usize, Map<T>)>;
+
+/// Find the suspicious parts of the sections of an almanac.
+fn lint<T: Value>(s: &str) -> Result<Vec<Lint<T>>, InvalidInput> {
+    /// Check the maps of a section, along with their lines.
+    fn lint_section<T: Value>(
+        section: &str,
+        maps: &mut [(usize, Map<T>)],
+        lints: &mut Vec<Lint<T>>,
+    ) {
+        // Check every pair of maps.
+        for (i, (first_line, first)) in maps.iter().enumerate() {
+            for (second_line, second) in &maps[i + 1..] {
+                if overlap(&first.source, &second.source) {
+                    lints.push(Lint::OverlappingSources {
+                        section: section.into(),
+                        lines: (*first_line, *second_line),
+                    });
+                }
+                if overlap(&first.destination, &second.destination) {
+                    lints.push(Lint::OverlappingDestinations {
+                        section: section.into(),
+                        lines: (*first_line, *second_line),
+                    });
+                }
+            }
+        }
+
+        // Find the gaps between the sources.
+        maps.sort_unstable_by_key(|(_line, map)| *map.source.start());
+        let mut covered: Option<(usize, T)> = None;
+        for (line, map) in maps.iter() {
+            if let Some((end_line, end)) = covered {
+                let next = end.checked_add(T::ONE);
+                let last = map.source.start().checked_sub(T::ONE);
+                if let Some((next, last)) = next.zip(last).filter(|(next, last)| next <= last) {
+                    lints.push(Lint::IdentityGap {
+                        section: section.into(),
+                        range: next..=last,
+                        lines: (end_line, *line),
+                    });
+                }
+            }
+            if covered.is_none_or(|(_line, end)| end < *map.source.end()) {
+                covered = Some((*line, *map.source.end()));
+            }
+        }
+    }
+
+    let mut lints = Vec::new();
+    let mut section: Option<(&str, NumberedMaps<T>)> = None;
+
+    // Skip the seeds.
+    for (index, line) in s.trim_end().lines().enumerate().skip(1) {
+        if let Some(header) = line.strip_suffix(" map:") {
+            section = Some((header, Vec::new()));
+        } else if line.is_empty() {
+            // End of the section.
+            if let Some((header, mut maps)) = section.take() {
+                lint_section(header, &mut maps, &mut lints);
+            }
+        } else {
+            let (header, maps) = section.as_mut().ok_or(InvalidInput::Other)?;
+            match line.parse() {
+                Ok(map) => maps.push((index + 1, map)),
+                Err(InvalidInput::ZeroLength) => lints.push(Lint::ZeroLength {
+                    section: (*header).into(),
+                    line: index + 1,
+                }),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+    if let Some((header, mut maps)) = section {
+        lint_section(header, &mut maps, &mut lints);
+    }
+
+    Ok(lints)
+}
+
+/// Run a day 5 command.
+///
+/// - `lowest [single|ranges]` prints the path of the seed leading to the
+///   lowest location, reading the seeds as single values (by default) or as
+///   ranges.
+/// - `seeds LOCATION` prints the seed ranges ending at a location.
+/// - `map FROM TO VALUE` prints the path of a value from a category to
+///   another one.
+/// - `lint` prints the suspicious parts of the almanac sections.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day5 lowest [single|ranges]
+       day5 seeds LOCATION
+       day5 map FROM TO VALUE
+       day5 lint";
+
+    /// Print a path, one category per line.
+    fn print_path(path: &[(&str, u64)]) {
+        for (category, value) in path {
+            println!("{category}: {value}");
+        }
+    }
+
+    let input: Input<u64> = INPUT.parse().map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("lowest") => {
+            let seeds = match args.next().as_deref() {
+                None | Some("single") => input.seeds.iter().map(|seed| *seed..=*seed).collect(),
+                Some("ranges") => input.seed_ranges().map_err(|_| "Overflow")?,
+                Some(_) => return Err(USAGE.into()),
+            };
+            let path = input
+                .lowest_path("seed", "location", &seeds)
+                .map_err(|error| format!("{error:?}"))?
+                .ok_or("Empty seed list")?;
+            print_path(&path);
+        }
+        Some("seeds") => {
+            let location: u64 = args.next().and_then(|arg| arg.parse().ok()).ok_or(USAGE)?;
+            let seeds = input
+                .preimage("seed", "location", &IntervalSet::from(location..=location))
+                .map_err(|error| format!("{error:?}"))?;
+            for range in seeds.iter() {
+                println!("{}..={}", range.start(), range.end());
+            }
+        }
+        Some("map") => {
+            let (Some(from), Some(to), Some(value)) = (args.next(), args.next(), args.next())
+            else {
+                return Err(USAGE.into());
+            };
+            let value = value.parse().map_err(|_| USAGE)?;
+            let path = input
+                .path(&from, &to, value)
+                .map_err(|error| format!("{error:?}"))?;
+            print_path(&path);
+        }
+        Some("lint") => {
+            let lints = lint::<u64>(INPUT).map_err(|error| format!("{error:?}"))?;
+            for lint in &lints {
+                println!("{lint}");
+            }
+            println!("{} issues", lints.len());
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Input, Map};
+    use super::{lint, Input, InvalidInput, Lint, Map, Overflow, QueryError};
+    use crate::interval::{IntervalSet, Value};
+
+    /// Step by step mapping, to check the composed map.
+    impl<T: Value> Input<T> {
+        /// Map a seed to a location.
+        fn seed_to_location(&self, seed: T) -> Result<T, QueryError> {
+            // Fold the maps.
+            self.chain("seed", "location")?
+                .into_iter()
+                .try_fold(seed, |source, section| {
+                    Ok(section.maps.piecewise()?.map(source)?)
+                })
+        }
+
+        /// Map a set of seeds to a set of locations.
+        fn seeds_to_locations(&self, seeds: &IntervalSet<T>) -> Result<IntervalSet<T>, QueryError> {
+            // Fold the maps.
+            self.chain("seed", "location")?
+                .into_iter()
+                .try_fold(seeds.clone(), |sources, section| {
+                    Ok(section.maps.piecewise()?.map_set(&sources)?)
+                })
+        }
+    }
 
-    impl Map {
+    impl Map<u32> {
         const fn new(destination_start: u32, source_start: u32, range_length: u32) -> Self {
             Self {
                 destination: destination_start..=destination_start + range_length - 1,
@@ -306,15 +577,7 @@ mod tests {
         }
     }
 
-    #[test]
-    fn parse_map() {
-        assert_eq!("50 98 1".parse(), Ok(Map::new(50, 98, 1)));
-        assert_eq!("52 50 48".parse(), Ok(Map::new(52, 50, 48)));
-    }
-
-    #[test]
-    fn parse_input() {
-        const INPUT: &str = "seeds: 79 14 55 13
+    const INPUT: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
 50 98 2
@@ -347,14 +610,94 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let input: Input = INPUT.parse().expect("Invalid input");
+
+    #[test]
+    fn parse_map() {
+        assert_eq!("50 98 1".parse(), Ok(Map::new(50, 98, 1)));
+        assert_eq!("52 50 48".parse(), Ok(Map::new(52, 50, 48)));
+        assert_eq!("52 50 0".parse::<Map<u32>>(), Err(InvalidInput::ZeroLength));
+        assert_eq!("52 50 1 1".parse::<Map<u32>>(), Err(InvalidInput::Other));
+    }
+
+    #[test]
+    fn overflow() {
+        // The ranges must fit in the value type.
+        assert_eq!(
+            "4294967295 0 2".parse::<Map<u32>>(),
+            Err(InvalidInput::Overflow)
+        );
+        assert_eq!(
+            "4294967295 0 2".parse::<Map<u64>>(),
+            Ok(Map {
+                destination: 4_294_967_295..=4_294_967_296,
+                source: 0..=1,
+            })
+        );
+        assert_eq!(
+            "0 340282366920938463463374607431768211455 1".parse::<Map<u128>>(),
+            Ok(Map {
+                destination: 0..=0,
+                source: u128::MAX..=u128::MAX,
+            })
+        );
+
+        // The seed ranges must fit in the value type.
+        let input: Input<u32> = "seeds: 4294967295 2
+
+seed-to-soil map:
+
+soil-to-fertilizer map:
+
+fertilizer-to-water map:
+
+water-to-light map:
+
+light-to-temperature map:
+
+temperature-to-humidity map:
+
+humidity-to-location map:"
+            .parse()
+            .unwrap();
+        assert_eq!(input.seed_ranges(), Err(Overflow));
+
+        // Mapping the whole value range.
+        let input: Input<u32> = "seeds: 4294967295 1
+
+seed-to-soil map:
+0 4294967295 1
+
+soil-to-fertilizer map:
+
+fertilizer-to-water map:
+
+water-to-light map:
+
+light-to-temperature map:
+
+temperature-to-humidity map:
+
+humidity-to-location map:"
+            .parse()
+            .unwrap();
+        assert_eq!(input.seed_to_location(u32::MAX), Ok(0));
+        // Everything is mapped to 0 or left unchanged.
+        assert_eq!(
+            input.seeds_to_locations(&IntervalSet::full()),
+            Ok(IntervalSet::from(0..=u32::MAX - 1))
+        );
+    }
+
+    #[test]
+    fn parse_input() {
+        let input: Input<u32> = INPUT.parse().expect("Invalid input");
         assert_eq!(input.seeds, [79, 14, 55, 13]);
         assert_eq!(
-            input.seed_to_soil.0,
+            input.sections[0].maps.0,
             vec![Map::new(50, 98, 2), Map::new(52, 50, 48)]
         );
         assert_eq!(
-            input.soil_to_fertilizer.0,
+            input.sections[1].maps.0,
             [
                 Map::new(0, 15, 37),
                 Map::new(37, 52, 2),
@@ -362,7 +705,7 @@ humidity-to-location map:
             ]
         );
         assert_eq!(
-            input.fertilizer_to_water.0,
+            input.sections[2].maps.0,
             [
                 Map::new(49, 53, 8),
                 Map::new(0, 11, 42),
@@ -371,11 +714,11 @@ humidity-to-location map:
             ]
         );
         assert_eq!(
-            input.water_to_light.0,
+            input.sections[3].maps.0,
             [Map::new(88, 18, 7), Map::new(18, 25, 70)]
         );
         assert_eq!(
-            input.light_to_temperature.0,
+            input.sections[4].maps.0,
             [
                 Map::new(45, 77, 23),
                 Map::new(81, 45, 19),
@@ -383,12 +726,183 @@ humidity-to-location map:
             ]
         );
         assert_eq!(
-            input.temperature_to_humidity.0,
+            input.sections[5].maps.0,
             [Map::new(0, 69, 1), Map::new(1, 0, 69)]
         );
         assert_eq!(
-            input.humidity_to_location.0,
+            input.sections[6].maps.0,
             [Map::new(60, 56, 37), Map::new(56, 93, 4)]
         );
     }
+
+    #[test]
+    fn locations() {
+        let input: Input<u32> = INPUT.parse().expect("Invalid input");
+        let locations = input
+            .seeds
+            .iter()
+            .map(|seed| input.seed_to_location(*seed).unwrap());
+        assert_eq!(locations.collect::<Vec<_>>(), [82, 43, 86, 35]);
+        let seeds = input.seed_ranges().unwrap();
+        let locations = input.seeds_to_locations(&seeds).unwrap();
+        assert_eq!(locations.min(), Some(46));
+
+        // The composed map is equivalent to the step by step fold.
+        let map = input.map_between("seed", "location").unwrap();
+        for seed in 0..=200 {
+            assert_eq!(map.map(seed).ok(), input.seed_to_location(seed).ok());
+        }
+        assert_eq!(map.map_set(&seeds), Ok(locations));
+        let all = IntervalSet::full();
+        assert_eq!(map.map_set(&all).ok(), input.seeds_to_locations(&all).ok());
+    }
+
+    #[test]
+    fn reverse_queries() {
+        let input: Input<u32> = INPUT.parse().expect("Invalid input");
+        let map = input.map_between("seed", "location").unwrap();
+        let seeds = input.seed_ranges().unwrap();
+        let seeds_at_46 = input
+            .preimage("seed", "location", &IntervalSet::from(46..=46))
+            .unwrap();
+        assert!(seeds_at_46.contains(82));
+        for seed in seeds_at_46.iter().flat_map(Clone::clone) {
+            assert_eq!(map.map(seed), Ok(46));
+        }
+        assert_eq!(
+            input.lowest_path("seed", "location", &seeds),
+            Ok(Some(
+                [
+                    ("seed", 82),
+                    ("soil", 84),
+                    ("fertilizer", 84),
+                    ("water", 84),
+                    ("light", 77),
+                    ("temperature", 45),
+                    ("humidity", 46),
+                    ("location", 46)
+                ]
+                .into()
+            ))
+        );
+        let single: IntervalSet<u32> = input.seeds.iter().map(|seed| *seed..=*seed).collect();
+        assert_eq!(
+            input.lowest_path("seed", "location", &single),
+            Ok(Some(
+                [
+                    ("seed", 13),
+                    ("soil", 13),
+                    ("fertilizer", 52),
+                    ("water", 41),
+                    ("light", 34),
+                    ("temperature", 34),
+                    ("humidity", 35),
+                    ("location", 35)
+                ]
+                .into()
+            ))
+        );
+        assert_eq!(
+            input.lowest_path("seed", "location", &IntervalSet::empty()),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn category_graph() {
+        // Reordered sections, with an extra stage and a dead end.
+        const INPUT: &str = "seeds: 1 2
+
+water-to-location map:
+100 0 10
+
+seed-to-soil map:
+5 0 5
+
+soil-to-water map:
+0 5 1
+
+soil-to-ore map:
+0 0 100";
+        let input: Input<u32> = INPUT.parse().unwrap();
+        assert_eq!(input.sections.len(), 4);
+        assert_eq!(
+            input.path("seed", "location", 0),
+            Ok(vec![
+                ("seed", 0),
+                ("soil", 5),
+                ("water", 0),
+                ("location", 100)
+            ])
+        );
+        assert_eq!(
+            input.path("soil", "ore", 7),
+            Ok(vec![("soil", 7), ("ore", 7)])
+        );
+        assert_eq!(input.path("seed", "seed", 3), Ok(vec![("seed", 3)]));
+        assert_eq!(input.chain("ore", "seed").unwrap_err(), QueryError::NoPath);
+        assert_eq!(
+            input.map_between("location", "seed").unwrap_err(),
+            QueryError::NoPath
+        );
+
+        // The headers must name two categories.
+        assert!("seeds: 1\n\n-to-soil map:\n1 2 3"
+            .parse::<Input<u32>>()
+            .is_err());
+        assert!("seeds: 1\n\nseed-soil map:\n1 2 3"
+            .parse::<Input<u32>>()
+            .is_err());
+    }
+
+    #[test]
+    fn lints() {
+        const INPUT: &str = "seeds: 1 2
+
+seed-to-soil map:
+50 98 2
+52 50 48
+0 99 5
+10 20 0
+
+soil-to-water map:
+0 0 10
+5 20 10
+100 40 5";
+        // The example does not have any issue.
+        assert_eq!(lint::<u32>(super::tests::INPUT), Ok(vec![]));
+
+        let lints = lint::<u32>(INPUT).unwrap();
+        assert_eq!(
+            lints,
+            [
+                Lint::ZeroLength {
+                    section: "seed-to-soil".into(),
+                    line: 7
+                },
+                Lint::OverlappingSources {
+                    section: "seed-to-soil".into(),
+                    lines: (4, 6)
+                },
+                Lint::OverlappingDestinations {
+                    section: "soil-to-water".into(),
+                    lines: (10, 11)
+                },
+                Lint::IdentityGap {
+                    section: "soil-to-water".into(),
+                    range: 10..=19,
+                    lines: (10, 11)
+                },
+                Lint::IdentityGap {
+                    section: "soil-to-water".into(),
+                    range: 30..=39,
+                    lines: (11, 12)
+                },
+            ]
+        );
+        assert_eq!(
+            lints[3].to_string(),
+            "soil-to-water: sources 10..=19 between lines 10 and 11 are not mapped"
+        );
+    }
 }
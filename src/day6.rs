@@ -1,12 +1,25 @@
-use std::{num::ParseIntError, ops::Range, str::FromStr};
+use std::{
+    fmt,
+    iter::Product,
+    num::ParseIntError,
+    ops::{Mul, Range},
+    str::FromStr,
+};
 
 const INPUT: &str = include_str!("day6.txt");
 
 /// Invalid input.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum InvalidInput {
     /// Invalid integer.
     ParseInt(ParseIntError),
+    /// The lines do not have the same amount of entries.
+    CountMismatch {
+        /// Amount of times.
+        times: usize,
+        /// Amount of distances.
+        distances: usize,
+    },
     /// Another parsing error.
     Other,
 }
@@ -17,77 +30,205 @@ impl From<ParseIntError> for InvalidInput {
     }
 }
 
+/// An unbounded natural number, to multiply counts without overflow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Natural(
+    /// Digits in base 2⁶⁴, least significant first, without trailing zeros.
+    Vec<u64>,
+);
+
+/// Multiply by a machine integer.
+impl Mul<u64> for Natural {
+    type Output = Self;
+
+    // Truncations keep the lower half of double width products.
+    #[allow(clippy::cast_possible_truncation)]
+    fn mul(self, rhs: u64) -> Self::Output {
+        let mut carry = 0;
+        let mut digits: Vec<u64> = self
+            .0
+            .into_iter()
+            .map(|digit| {
+                let product = u128::from(digit) * u128::from(rhs) + carry;
+                carry = product >> u64::BITS;
+                // Keep the lower half.
+                product as u64
+            })
+            .collect();
+        if rhs == 0 {
+            digits.clear();
+        } else if carry > 0 {
+            digits.push(carry as u64);
+        }
+        Self(digits)
+    }
+}
+
+/// Multiply machine integers.
+impl Product<u64> for Natural {
+    fn product<I: Iterator<Item = u64>>(iter: I) -> Self {
+        iter.fold(Self(vec![1]), Mul::mul)
+    }
+}
+
+/// Print in base 10.
+impl fmt::Display for Natural {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Largest power of 10 fitting in a digit.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+
+        // Divide by the chunk until 0, collecting the remainders.
+        let mut digits = self.0.clone();
+        let mut chunks = Vec::new();
+        while !digits.is_empty() {
+            let mut remainder = 0;
+            for digit in digits.iter_mut().rev() {
+                let value = remainder << u64::BITS | u128::from(*digit);
+                // The quotient fits since the remainder is below the chunk.
+                *digit = u64::try_from(value / u128::from(CHUNK)).expect("Quotient overflow");
+                remainder = value % u128::from(CHUNK);
+            }
+            chunks.push(remainder);
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+        }
+
+        // Print the most significant chunk, then the padded ones.
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{first}")?;
+                rest.iter()
+                    .rev()
+                    .try_for_each(|chunk| write!(f, "{chunk:019}"))
+            }
+        }
+    }
+}
+
 /// A race.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 struct Race {
     /// Duration of the race.
-    time: u32,
+    time: u64,
     /// Record distance.
-    distance: u64,
+    distance: u128,
 }
 
 impl Race {
     /// Compute a distance in function of an hold time.
-    fn score(&self, hold_time: u32) -> u64 {
-        u64::from(self.time - hold_time) * u64::from(hold_time)
+    /// It cannot overflow since it is at most (2⁶⁴)² / 4.
+    fn score(&self, hold_time: u64) -> u128 {
+        u128::from(self.time - hold_time) * u128::from(hold_time)
+    }
+
+    /// Check whether an hold time beats the record.
+    fn beats(&self, hold_time: u64) -> bool {
+        self.score(hold_time) > self.distance
     }
 
     /// Get the range of hold times that can beat the record.
-    fn beatable_range(self) -> Range<u32> {
-        (0..self.time)
-            .find(|time| self.score(*time) > self.distance)
-            .map_or(0..0, |start| {
-                let end = self.time - start + 1;
-                start..end
-            })
+    fn beatable_range(self) -> Range<u64> {
+        // The score is the highest at the half of the race.
+        let half = self.time / 2;
+        if !self.beats(half) {
+            return 0..0;
+        }
+
+        // Solve hold * (time - hold) > distance,
+        //   i.e. hold² - time * hold + distance < 0.
+        // Since the half beats the record, 4 * distance < time², which fits.
+        let time = u128::from(self.time);
+        let discriminant = time * time - 4 * self.distance;
+        let root = (time - discriminant.isqrt()) / 2;
+        let mut start = u64::try_from(root).map_or(half, |root| root.min(half));
+
+        // Correct the rounding of the square root.
+        while !self.beats(start) {
+            start += 1;
+        }
+        while start > 0 && self.beats(start - 1) {
+            start -= 1;
+        }
+
+        // The scores are symmetric.
+        let end = self.time - start + 1;
+        start..end
     }
 }
 
-// A list of races.
-struct Races(Vec<Race>);
-
-/// Parse the input.
-impl FromStr for Races {
-    type Err = InvalidInput;
+/// How to read the sheet of paper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kerning {
+    /// Each column is a race.
+    Columns,
+    /// The spaces are a kerning mistake, there is a single race.
+    Kerned,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.trim_end().lines();
+/// A list of races.
+struct Races(Vec<Race>);
 
-        // Parse the times.
-        let times = lines
-            .next()
+impl Races {
+    /// Parse the entries of a line after its prefix.
+    fn entries<'a>(line: Option<&'a str>, prefix: &str) -> Result<Vec<&'a str>, InvalidInput> {
+        Ok(line
             .ok_or(InvalidInput::Other)?
-            .strip_prefix("Time:")
+            .strip_prefix(prefix)
             .ok_or(InvalidInput::Other)?
-            .trim_start()
             .split_ascii_whitespace()
-            .map(str::parse);
+            .collect())
+    }
 
-        // Parse the distances.
-        let distances = lines
-            .next()
-            .ok_or(InvalidInput::Other)?
-            .strip_prefix("Distance:")
-            .ok_or(InvalidInput::Other)?
-            .trim_start()
-            .split_ascii_whitespace()
-            .map(str::parse);
-
-        // Zip lists together.
-        times
-            .zip(distances)
-            .map(|(time, distance)| {
-                Ok(Race {
-                    time: time?,
-                    distance: distance?,
-                })
+    /// Parse the input, reading it with some kerning.
+    fn parse(s: &str, kerning: Kerning) -> Result<Self, InvalidInput> {
+        let mut lines = s.trim_end().lines();
+        let times = Self::entries(lines.next(), "Time:")?;
+        let distances = Self::entries(lines.next(), "Distance:")?;
+        if lines.next().is_some() {
+            return Err(InvalidInput::Other);
+        }
+
+        let race = |time: &str, distance: &str| -> Result<Race, InvalidInput> {
+            Ok(Race {
+                time: time.parse()?,
+                distance: distance.parse()?,
             })
-            .collect::<Result<_, _>>()
-            .map(Races)
+        };
+        match kerning {
+            Kerning::Columns => {
+                // Every time needs its distance.
+                if times.len() != distances.len() {
+                    return Err(InvalidInput::CountMismatch {
+                        times: times.len(),
+                        distances: distances.len(),
+                    });
+                }
+                // Zip lists together.
+                times
+                    .into_iter()
+                    .zip(distances)
+                    .map(|(time, distance)| race(time, distance))
+                    .collect::<Result<_, _>>()
+                    .map(Races)
+            }
+            // Join the entries of each line in a single one.
+            Kerning::Kerned => Ok(Self(vec![race(&times.concat(), &distances.concat())?])),
+        }
     }
 }
 
-pub fn first_part() -> u32 {
+/// Parse the input, one race per column.
+impl FromStr for Races {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Kerning::Columns)
+    }
+}
+
+pub fn first_part() -> Natural {
     // Parse the input.
     INPUT
         .parse::<Races>()
@@ -102,12 +243,10 @@ pub fn first_part() -> u32 {
         .product()
 }
 
-pub fn second_part() -> u32 {
+pub fn second_part() -> u64 {
     let range =
-    // Parse the input (strip spaces).
-    INPUT
-        .replace(' ', "")
-        .parse::<Races>()
+    // Parse the input (ignore the kerning).
+    Races::parse(INPUT, Kerning::Kerned)
         .expect("Invalid input")
         .0
         // Get the lone race.
@@ -119,3 +258,121 @@ pub fn second_part() -> u32 {
     // Compute the amount of possibilities.
     range.end - range.start
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{InvalidInput, Kerning, Natural, Race, Races};
+    use std::ops::Range;
+
+    /// Create a race.
+    const fn race(time: u64, distance: u128) -> Race {
+        Race { time, distance }
+    }
+
+    impl Race {
+        /// Search the beatable range one hold time after the other.
+        fn brute_force_range(&self) -> Range<u64> {
+            (0..self.time)
+                .find(|time| self.beats(*time))
+                .map_or(0..0, |start| {
+                    let end = self.time - start + 1;
+                    start..end
+                })
+        }
+    }
+
+    #[test]
+    fn beatable_range() {
+        assert_eq!(race(7, 9).beatable_range(), 2..6);
+        assert_eq!(race(15, 40).beatable_range(), 4..12);
+        assert_eq!(race(30, 200).beatable_range(), 11..20);
+        assert_eq!(race(71_530, 940_200).beatable_range().count(), 71_503);
+        // The record is exactly reachable, but not beatable.
+        assert_eq!(race(10, 25).beatable_range(), 0..0);
+        // No winning time.
+        assert_eq!(race(10, 100).beatable_range(), 0..0);
+        // No time at all.
+        assert_eq!(race(0, 0).beatable_range(), 0..0);
+    }
+
+    #[test]
+    fn beatable_range_against_brute_force() {
+        for time in 0..100 {
+            for distance in 0..=u128::from(time * time / 4 + 1) {
+                let race = Race { time, distance };
+                let expected = race.brute_force_range();
+                assert_eq!(
+                    race.beatable_range(),
+                    expected,
+                    "time {time}, distance {distance}"
+                );
+            }
+        }
+        // Largest values.
+        let race = Race {
+            time: u64::MAX,
+            distance: 0,
+        };
+        assert_eq!(race.beatable_range(), 1..u64::MAX);
+        let race = Race {
+            time: u64::MAX,
+            distance: u128::from(u64::MAX / 2) * u128::from(u64::MAX / 2 + 1) - 1,
+        };
+        assert_eq!(race.beatable_range(), u64::MAX / 2..u64::MAX / 2 + 2);
+        let race = Race {
+            time: u64::MAX,
+            distance: u128::MAX,
+        };
+        assert_eq!(race.beatable_range(), 0..0);
+    }
+
+    #[test]
+    fn natural() {
+        let product = |values: &[u64]| values.iter().copied().product::<Natural>().to_string();
+        assert_eq!(product(&[]), "1");
+        assert_eq!(product(&[4, 8, 9]), "288");
+        assert_eq!(product(&[12, 0, 7]), "0");
+        assert_eq!(
+            product(&[u64::MAX, u64::MAX]),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(
+            product(&[u64::MAX, u64::MAX, u64::MAX]),
+            "6277101735386680762814942322444851025767571854389858533375"
+        );
+        assert_eq!(
+            product(&[10_000_000_000_000_000_000, 10]),
+            "100000000000000000000"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        const INPUT: &str = "Time:      7  15   30
+Distance:  9  40  200
+";
+        let races: Races = INPUT.parse().unwrap();
+        assert_eq!(races.0, [race(7, 9), race(15, 40), race(30, 200)]);
+        let races = Races::parse(INPUT, Kerning::Kerned).unwrap();
+        assert_eq!(races.0, [race(71_530, 940_200)]);
+
+        // Mismatching entries.
+        assert_eq!(
+            Races::parse("Time: 7 15\nDistance: 9", Kerning::Columns).err(),
+            Some(InvalidInput::CountMismatch {
+                times: 2,
+                distances: 1
+            })
+        );
+        let races = Races::parse("Time: 7 15 30\nDistance: 940 200", Kerning::Kerned).unwrap();
+        assert_eq!(races.0, [race(71_530, 940_200)]);
+        assert_eq!(
+            Races::parse("Time: 7\nDistance: 9\nTime: 8", Kerning::Columns).err(),
+            Some(InvalidInput::Other)
+        );
+        assert_eq!(
+            Races::parse("Time: 7", Kerning::Columns).err(),
+            Some(InvalidInput::Other)
+        );
+    }
+}
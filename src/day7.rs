@@ -0,0 +1,262 @@
+use std::{num::ParseIntError, str::FromStr};
+
+const INPUT: &str = include_str!("day7.txt");
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Invalid integer.
+    ParseInt(ParseIntError),
+    /// Unknown card label.
+    Card(char),
+    /// A hand does not have five cards.
+    HandSize(usize),
+    /// Another parsing error.
+    Other,
+}
+
+impl From<ParseIntError> for InvalidInput {
+    fn from(error: ParseIntError) -> Self {
+        Self::ParseInt(error)
+    }
+}
+
+/// A card, by its face value (2 to 14 for an ace).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Card(u8);
+
+impl Card {
+    /// The jack, which is a joker with some rules.
+    const JACK: Self = Self(11);
+}
+
+/// Parse a card label.
+impl TryFrom<char> for Card {
+    type Error = InvalidInput;
+
+    fn try_from(label: char) -> Result<Self, Self::Error> {
+        let value = match label {
+            'A' => 14,
+            'K' => 13,
+            'Q' => 12,
+            'J' => 11,
+            'T' => 10,
+            '2'..='9' => label as u8 - b'0',
+            _ => return Err(InvalidInput::Card(label)),
+        };
+        Ok(Self(value))
+    }
+}
+
+/// The type of a hand, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum HandType {
+    /// All the labels are distinct.
+    HighCard,
+    /// Two cards share a label.
+    OnePair,
+    /// Two pairs of cards share a label.
+    TwoPair,
+    /// Three cards share a label.
+    ThreeOfAKind,
+    /// Three cards share a label, the two others another one.
+    FullHouse,
+    /// Four cards share a label.
+    FourOfAKind,
+    /// All the cards share a label.
+    FiveOfAKind,
+}
+
+impl HandType {
+    /// Classify the counts of each label, from the largest to the smallest.
+    fn from_counts(counts: &[usize]) -> Self {
+        match counts {
+            [5, ..] => Self::FiveOfAKind,
+            [4, ..] => Self::FourOfAKind,
+            [3, 2, ..] => Self::FullHouse,
+            [3, ..] => Self::ThreeOfAKind,
+            [2, 2, ..] => Self::TwoPair,
+            [2, ..] => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
+/// How to rank the hands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rules {
+    /// The jacks are regular cards.
+    Standard,
+    /// The jacks are jokers: the weakest card on its own, but acting like
+    ///   whatever card makes the strongest hand type.
+    Jokers,
+}
+
+impl Rules {
+    /// Get the strength of a card, to break ties.
+    const fn strength(self, card: Card) -> u8 {
+        match self {
+            Self::Jokers if card.0 == Card::JACK.0 => 1,
+            _ => card.0,
+        }
+    }
+
+    /// Get the type of a hand.
+    fn hand_type(self, cards: [Card; 5]) -> HandType {
+        // Count the cards per label, setting the jokers aside.
+        let mut jokers = 0;
+        let mut counts = Vec::with_capacity(cards.len());
+        let mut labels = cards;
+        labels.sort_unstable();
+        for chunk in labels.chunk_by(|first, second| first == second) {
+            if self == Self::Jokers && chunk[0] == Card::JACK {
+                jokers = chunk.len();
+            } else {
+                counts.push(chunk.len());
+            }
+        }
+        counts.sort_unstable_by(|first, second| second.cmp(first));
+
+        // The jokers are best used to extend the largest group.
+        match counts.first_mut() {
+            Some(largest) => *largest += jokers,
+            None => counts.push(jokers),
+        }
+        HandType::from_counts(&counts)
+    }
+
+    /// Get the key ordering the hands, from the weakest to the strongest.
+    fn key(self, hand: &Hand) -> (HandType, [u8; 5]) {
+        (
+            self.hand_type(hand.cards),
+            hand.cards.map(|card| self.strength(card)),
+        )
+    }
+}
+
+/// A hand and its bid.
+#[derive(Debug, PartialEq, Eq)]
+struct Hand {
+    /// Cards, in order.
+    cards: [Card; 5],
+    /// Bid.
+    bid: u64,
+}
+
+/// Parse a hand and its bid.
+impl FromStr for Hand {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split the cards from the bid.
+        let (cards, bid) = s.split_once(' ').ok_or(InvalidInput::Other)?;
+        // Parse the cards.
+        let cards = cards
+            .chars()
+            .map(Card::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let cards =
+            <[Card; 5]>::try_from(cards).map_err(|cards| InvalidInput::HandSize(cards.len()))?;
+        // Parse the bid.
+        let bid = bid.parse()?;
+        Ok(Self { cards, bid })
+    }
+}
+
+/// Parse the hands, one per line.
+fn parse_hands(s: &str) -> Result<Vec<Hand>, InvalidInput> {
+    s.trim().lines().map(str::parse).collect()
+}
+
+/// Compute the total winnings: each bid multiplied by the rank of its hand.
+fn winnings(mut hands: Vec<Hand>, rules: Rules) -> u64 {
+    hands.sort_by_cached_key(|hand| rules.key(hand));
+    hands
+        .iter()
+        .zip(1..)
+        .map(|(hand, rank)| hand.bid * rank)
+        .sum()
+}
+
+pub fn first_part() -> u64 {
+    winnings(parse_hands(INPUT).expect("Invalid input"), Rules::Standard)
+}
+
+pub fn second_part() -> u64 {
+    winnings(parse_hands(INPUT).expect("Invalid input"), Rules::Jokers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_hands, winnings, Card, Hand, HandType, InvalidInput, Rules};
+
+    const INPUT: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    /// Get the type of a hand given by its labels.
+    fn hand_type(cards: &str, rules: Rules) -> HandType {
+        let hand: Hand = format!("{cards} 0").parse().unwrap();
+        rules.hand_type(hand.cards)
+    }
+
+    #[test]
+    fn parse() {
+        let hands = parse_hands(INPUT).unwrap();
+        assert_eq!(hands.len(), 5);
+        assert_eq!(
+            hands[0],
+            Hand {
+                cards: [Card(3), Card(2), Card(10), Card(3), Card(13)],
+                bid: 765
+            }
+        );
+        assert_eq!("32T3 765".parse::<Hand>(), Err(InvalidInput::HandSize(4)));
+        assert_eq!("32X3K 765".parse::<Hand>(), Err(InvalidInput::Card('X')));
+        assert_eq!("32T3K".parse::<Hand>(), Err(InvalidInput::Other));
+        assert!(matches!(
+            "32T3K -1".parse::<Hand>(),
+            Err(InvalidInput::ParseInt(_))
+        ));
+    }
+
+    #[test]
+    fn hand_types() {
+        assert_eq!(hand_type("AAAAA", Rules::Standard), HandType::FiveOfAKind);
+        assert_eq!(hand_type("AA8AA", Rules::Standard), HandType::FourOfAKind);
+        assert_eq!(hand_type("23332", Rules::Standard), HandType::FullHouse);
+        assert_eq!(hand_type("TTT98", Rules::Standard), HandType::ThreeOfAKind);
+        assert_eq!(hand_type("23432", Rules::Standard), HandType::TwoPair);
+        assert_eq!(hand_type("A23A4", Rules::Standard), HandType::OnePair);
+        assert_eq!(hand_type("23456", Rules::Standard), HandType::HighCard);
+        assert_eq!(hand_type("KTJJT", Rules::Standard), HandType::TwoPair);
+        assert_eq!(hand_type("KTJJT", Rules::Jokers), HandType::FourOfAKind);
+        assert_eq!(hand_type("JJJJJ", Rules::Jokers), HandType::FiveOfAKind);
+        assert_eq!(hand_type("2345J", Rules::Jokers), HandType::OnePair);
+        assert_eq!(hand_type("2233J", Rules::Jokers), HandType::FullHouse);
+    }
+
+    #[test]
+    fn ordering() {
+        let key = |cards: &str, rules: Rules| {
+            let hand: Hand = format!("{cards} 0").parse().unwrap();
+            rules.key(&hand)
+        };
+        // Ties are broken by the first differing card.
+        assert!(key("33332", Rules::Standard) > key("2AAAA", Rules::Standard));
+        assert!(key("77888", Rules::Standard) > key("77788", Rules::Standard));
+        // Jokers are the weakest cards on their own.
+        assert!(key("JKKK2", Rules::Jokers) < key("QQQQ2", Rules::Jokers));
+        assert!(key("JKKK2", Rules::Standard) < key("QQQQ2", Rules::Standard));
+        assert!(key("J2345", Rules::Jokers) < key("2J345", Rules::Jokers));
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(winnings(parse_hands(INPUT).unwrap(), Rules::Standard), 6440);
+        assert_eq!(winnings(parse_hands(INPUT).unwrap(), Rules::Jokers), 5905);
+    }
+}
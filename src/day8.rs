@@ -0,0 +1,476 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+const INPUT: &str = include_str!("day8.txt");
+
+/// Invalid input.
+#[derive(Debug, PartialEq, Eq)]
+enum InvalidInput {
+    /// Unknown instruction.
+    Instruction(char),
+    /// A node leads to a node which is not defined.
+    UnknownNode(String),
+    /// A node is defined twice.
+    DuplicateNode(String),
+    /// Another parsing error.
+    Other,
+}
+
+/// An instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Take the left branch.
+    Left,
+    /// Take the right branch.
+    Right,
+}
+
+/// Parse an instruction.
+impl TryFrom<char> for Direction {
+    type Error = InvalidInput;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            _ => Err(InvalidInput::Instruction(c)),
+        }
+    }
+}
+
+/// The path of a walker, which ends up looping since the states are finite.
+#[derive(Debug, PartialEq, Eq)]
+struct Cycle {
+    /// Step at which the loop starts.
+    start: u64,
+    /// Amount of steps of the loop.
+    length: u64,
+    /// Steps at which an end node is reached, before looping back.
+    ends: Vec<u64>,
+}
+
+/// Why the simultaneous arrival of the ghosts cannot be computed with the LCM
+///   of their periods.
+#[derive(Debug, PartialEq, Eq)]
+enum GhostError {
+    /// No node ends with `A`, so there is no ghost.
+    NoGhost,
+    /// The amount of steps does not fit.
+    Overflow,
+    /// A ghost never reaches an end node.
+    NoEnd {
+        /// Node of the ghost.
+        start: String,
+    },
+    /// A ghost reaches end nodes at steps which are not evenly spaced in its
+    ///   cycle, or before its cycle.
+    Irregular {
+        /// Node of the ghost.
+        start: String,
+        /// Its cycle.
+        cycle: Cycle,
+    },
+    /// A ghost first reaches an end node at a step which is not a multiple of
+    ///   its period.
+    OutOfPhase {
+        /// Node of the ghost.
+        start: String,
+        /// Step at which the end node is first reached.
+        end: u64,
+        /// Amount of steps between two end nodes.
+        period: u64,
+    },
+}
+
+/// Describe the broken assumption.
+impl fmt::Display for GhostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoGhost => write!(f, "no node ends with A, so there is no ghost"),
+            Self::Overflow => write!(f, "the amount of steps does not fit"),
+            Self::NoEnd { start } => write!(f, "ghost {start} never reaches an end node"),
+            Self::Irregular { start, cycle } => write!(
+                f,
+                "ghost {start} reaches end nodes at irregular steps {:?} \
+                 of its cycle of {} steps starting at step {}",
+                cycle.ends, cycle.length, cycle.start
+            ),
+            Self::OutOfPhase { start, end, period } => write!(
+                f,
+                "ghost {start} first reaches an end node at step {end}, \
+                 which is not a multiple of its period of {period} steps"
+            ),
+        }
+    }
+}
+
+impl Cycle {
+    /// Get the first step at which an end node is reached and the amount of
+    ///   steps until the next one, if they are evenly spaced forever.
+    fn period(&self) -> Option<(u64, u64)> {
+        let first = *self.ends.first()?;
+        let count = self.ends.len() as u64;
+        let period = self.length / count;
+        // The ends must all be in the cycle, and wrap around evenly.
+        (first >= self.start
+            && period * count == self.length
+            && (0..)
+                .map(|index| first + index * period)
+                .zip(&self.ends)
+                .all(|(expected, end)| expected == *end))
+        .then_some((first, period))
+    }
+}
+
+/// A network of nodes and the instructions to navigate it.
+#[derive(Debug)]
+struct Network {
+    /// Instructions, repeated forever.
+    instructions: Vec<Direction>,
+    /// Names of the nodes.
+    names: Vec<String>,
+    /// Left and right successors of the nodes.
+    nodes: Vec<[usize; 2]>,
+}
+
+impl Network {
+    /// Find a node by name.
+    fn node(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|other| other == name)
+    }
+
+    /// Walk from a node until a state repeats, noting the end nodes.
+    fn cycle(&self, start: usize, is_end: impl Fn(&str) -> bool) -> Cycle {
+        // A state is a node and an instruction index.
+        let len = self.instructions.len();
+        let mut visited = vec![None; self.nodes.len() * len];
+        let mut ends = Vec::new();
+        let mut node = start;
+        for step in 0.. {
+            let instruction = usize::try_from(step % len as u64).expect("Index overflow");
+            let state = &mut visited[node * len + instruction];
+            if let Some(first) = *state {
+                return Cycle {
+                    start: first,
+                    length: step - first,
+                    ends,
+                };
+            }
+            *state = Some(step);
+            if is_end(&self.names[node]) {
+                ends.push(step);
+            }
+            node = match self.instructions[instruction] {
+                Direction::Left => self.nodes[node][0],
+                Direction::Right => self.nodes[node][1],
+            };
+        }
+        unreachable!("The states are finite")
+    }
+
+    /// Count the steps from a node to another, if reachable.
+    fn steps(&self, from: &str, to: &str) -> Option<u64> {
+        let from = self.node(from)?;
+        self.cycle(from, |name| name == to).ends.first().copied()
+    }
+
+    /// Count the steps until all the ghosts simultaneously stand on a node
+    ///   ending with `Z`, having started on the nodes ending with `A`.
+    ///
+    /// This assumes that every ghost reaches end nodes periodically, first at
+    ///   a multiple of its period.
+    fn ghost_steps(&self) -> Result<u64, GhostError> {
+        let mut ghosts = self.ghost_cycles().peekable();
+        if ghosts.peek().is_none() {
+            return Err(GhostError::NoGhost);
+        }
+        ghosts
+            .try_fold((1, 0), |(lcm_period, last), (start, cycle)| {
+                let start = start.to_owned();
+                // Check the assumptions.
+                if cycle.ends.is_empty() {
+                    return Err(GhostError::NoEnd { start });
+                }
+                let Some((end, period)) = cycle.period() else {
+                    return Err(GhostError::Irregular { start, cycle });
+                };
+                if end % period != 0 {
+                    return Err(GhostError::OutOfPhase { start, end, period });
+                }
+                // The ghost reaches an end at every multiple of the period
+                //   from its first arrival.
+                let lcm_period = lcm(lcm_period, period).ok_or(GhostError::Overflow)?;
+                Ok((lcm_period, last.max(end)))
+            })
+            // Get the first common multiple after every first arrival.
+            .and_then(|(period, last)| {
+                last.div_ceil(period)
+                    .checked_mul(period)
+                    .ok_or(GhostError::Overflow)
+            })
+    }
+
+    /// Get the cycles of the ghosts, by starting node.
+    fn ghost_cycles(&self) -> impl '_ + Iterator<Item = (&str, Cycle)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| name.ends_with('A'))
+            .map(|(node, name)| (name.as_str(), self.cycle(node, |name| name.ends_with('Z'))))
+    }
+}
+
+/// Parse the instructions and the network.
+impl FromStr for Network {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (instructions, nodes) = s.trim().split_once("\n\n").ok_or(InvalidInput::Other)?;
+
+        // Parse the instructions.
+        let instructions = instructions
+            .chars()
+            .map(Direction::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        if instructions.is_empty() {
+            return Err(InvalidInput::Other);
+        }
+
+        // Split the nodes.
+        let nodes = nodes
+            .lines()
+            .map(|line| {
+                let (name, successors) = line.split_once(" = ").ok_or(InvalidInput::Other)?;
+                let (left, right) = successors
+                    .strip_prefix('(')
+                    .and_then(|successors| successors.strip_suffix(')'))
+                    .and_then(|successors| successors.split_once(", "))
+                    .ok_or(InvalidInput::Other)?;
+                Ok((name, left, right))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Index the names.
+        let mut indices = HashMap::new();
+        for (index, (name, _, _)) in nodes.iter().enumerate() {
+            if indices.insert(*name, index).is_some() {
+                return Err(InvalidInput::DuplicateNode((*name).to_owned()));
+            }
+        }
+        let index = |name: &str| {
+            indices
+                .get(name)
+                .copied()
+                .ok_or_else(|| InvalidInput::UnknownNode(name.to_owned()))
+        };
+
+        // Link the nodes.
+        let (names, nodes) = nodes
+            .iter()
+            .map(|(name, left, right)| Ok(((*name).to_owned(), [index(left)?, index(right)?])))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            instructions,
+            names,
+            nodes,
+        })
+    }
+}
+
+/// Compute the greatest common divisor.
+const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Compute the least common multiple, if it fits.
+const fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+pub fn first_part() -> u64 {
+    INPUT
+        .parse::<Network>()
+        .expect("Invalid input")
+        .steps("AAA", "ZZZ")
+        .expect("ZZZ is unreachable")
+}
+
+pub fn second_part() -> u64 {
+    INPUT
+        .parse::<Network>()
+        .expect("Invalid input")
+        .ghost_steps()
+        .unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Run a day 8 command.
+///
+/// - `ghosts` prints the cycle of each ghost, then the amount of steps until
+///   they all stand on an end node, or the assumption they break.
+pub fn command(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    const USAGE: &str = "Usage: day8 ghosts";
+
+    let network: Network = INPUT.parse().map_err(|_| "Invalid input")?;
+    match args.next().as_deref() {
+        Some("ghosts") => {
+            for (start, cycle) in network.ghost_cycles() {
+                println!(
+                    "{start}: cycle of {} steps from step {}, ends at steps {:?}",
+                    cycle.length, cycle.start, cycle.ends
+                );
+            }
+            let steps = network.ghost_steps().map_err(|error| error.to_string())?;
+            println!("All the ghosts arrive after {steps} steps");
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cycle, GhostError, InvalidInput, Network};
+
+    const FIRST_INPUT: &str = "RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const SECOND_INPUT: &str = "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const GHOST_INPUT: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    #[test]
+    fn parse() {
+        let network: Network = FIRST_INPUT.parse().unwrap();
+        assert_eq!(network.instructions.len(), 2);
+        assert_eq!(network.names.len(), 7);
+        assert_eq!(network.nodes[0], [1, 2]);
+        assert_eq!(
+            "RL\n\nAAA = (BBB, AAA)".parse::<Network>().err(),
+            Some(InvalidInput::UnknownNode("BBB".into()))
+        );
+        assert_eq!(
+            "RX\n\nAAA = (AAA, AAA)".parse::<Network>().err(),
+            Some(InvalidInput::Instruction('X'))
+        );
+        assert_eq!(
+            "R\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)"
+                .parse::<Network>()
+                .err(),
+            Some(InvalidInput::DuplicateNode("AAA".into()))
+        );
+        assert_eq!(
+            "R\nAAA = (AAA, AAA)".parse::<Network>().err(),
+            Some(InvalidInput::Other)
+        );
+    }
+
+    #[test]
+    fn steps() {
+        let network: Network = FIRST_INPUT.parse().unwrap();
+        assert_eq!(network.steps("AAA", "ZZZ"), Some(2));
+        assert_eq!(network.steps("BBB", "ZZZ"), None);
+        let network: Network = SECOND_INPUT.parse().unwrap();
+        assert_eq!(network.steps("AAA", "ZZZ"), Some(6));
+    }
+
+    #[test]
+    fn ghosts() {
+        let network: Network = GHOST_INPUT.parse().unwrap();
+        let cycles: Vec<_> = network.ghost_cycles().collect();
+        assert_eq!(
+            cycles,
+            [
+                (
+                    "11A",
+                    Cycle {
+                        start: 1,
+                        length: 2,
+                        ends: vec![2]
+                    }
+                ),
+                (
+                    "22A",
+                    Cycle {
+                        start: 1,
+                        length: 6,
+                        ends: vec![3, 6]
+                    }
+                ),
+            ]
+        );
+        assert_eq!(network.ghost_steps(), Ok(6));
+        let network: Network = super::INPUT.parse().unwrap();
+        assert_eq!(network.ghost_steps(), Ok(6));
+        let network: Network = FIRST_INPUT.parse().unwrap();
+        assert_eq!(network.ghost_steps(), Ok(2));
+
+        // Broken assumptions.
+        let network: Network =
+            "L\n\nAAA = (BBZ, BBZ)\nBBZ = (CCZ, CCZ)\nCCZ = (DDD, DDD)\nDDD = (BBZ, BBZ)"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            network.ghost_steps(),
+            Err(GhostError::Irregular {
+                start: "AAA".into(),
+                cycle: Cycle {
+                    start: 1,
+                    length: 3,
+                    ends: vec![1, 2]
+                }
+            })
+        );
+        let network: Network = "L\n\nAAA = (CCZ, CCZ)\nBBB = (CCZ, CCZ)\nCCZ = (BBB, BBB)"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            network.ghost_steps(),
+            Err(GhostError::OutOfPhase {
+                start: "AAA".into(),
+                end: 1,
+                period: 2
+            })
+        );
+        let network: Network = "L\n\nAAA = (BBB, BBB)\nBBB = (BBB, BBB)".parse().unwrap();
+        assert_eq!(
+            network.ghost_steps(),
+            Err(GhostError::NoEnd {
+                start: "AAA".into()
+            })
+        );
+        let network: Network = "L\n\nBBB = (BBB, BBB)".parse().unwrap();
+        assert_eq!(network.ghost_steps(), Err(GhostError::NoGhost));
+    }
+
+    #[test]
+    fn lcm() {
+        assert_eq!(super::lcm(4, 6), Some(12));
+        assert_eq!(super::lcm(1 << 32, 1 << 33), Some(1 << 33));
+        assert_eq!(super::lcm(u64::MAX, u64::MAX - 1), None);
+    }
+}
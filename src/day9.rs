@@ -0,0 +1,66 @@
+use crate::polynomial::Polynomial;
+use std::num::ParseIntError;
+
+const INPUT: &str = include_str!("day9.txt");
+
+/// Parse the sequences, one per line.
+fn parse_sequences(s: &str) -> Result<Vec<Vec<i64>>, ParseIntError> {
+    s.trim()
+        .lines()
+        .map(|line| line.split_ascii_whitespace().map(str::parse).collect())
+        .collect()
+}
+
+/// Sum the values extrapolated for each sequence, given the position of the
+///   extrapolated value relative to the sequence length.
+fn sum_extrapolated(s: &str, position: impl Fn(i64) -> i64) -> i64 {
+    // Parse the input.
+    parse_sequences(s)
+        .expect("Invalid input")
+        .iter()
+        // Fit and extrapolate each sequence.
+        .map(|values| {
+            let len = i64::try_from(values.len()).expect("Sequence too long");
+            Polynomial::fit(values)
+                .and_then(|polynomial| polynomial.at(position(len)))
+                .expect("Extrapolation overflow")
+        })
+        // Sum.
+        .sum()
+}
+
+pub fn first_part() -> i64 {
+    // The value after the last one.
+    sum_extrapolated(INPUT, |len| len)
+}
+
+pub fn second_part() -> i64 {
+    // The value before the first one.
+    sum_extrapolated(INPUT, |_| -1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_sequences, sum_extrapolated};
+
+    const INPUT: &str = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_sequences("1 -2 3\n-4 5").unwrap(),
+            [vec![1, -2, 3], vec![-4, 5]]
+        );
+        assert!(parse_sequences("1 - 2").is_err());
+    }
+
+    #[test]
+    fn extrapolate() {
+        assert_eq!(sum_extrapolated(INPUT, |len| len), 114);
+        assert_eq!(sum_extrapolated(INPUT, |_| -1), 2);
+        assert_eq!(sum_extrapolated("-1 -3 -5", |len| len), -7);
+    }
+}
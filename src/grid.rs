@@ -0,0 +1,266 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// Invalid grid input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidInput {
+    /// The input does not contain any cell.
+    Empty,
+    /// A row does not have the same width as the first one.
+    Ragged {
+        /// Index of the offending row.
+        row: usize,
+    },
+}
+
+/// A position in a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    /// Index of the row (from top to bottom).
+    pub row: usize,
+    /// Index of the column (from left to right).
+    pub column: usize,
+}
+
+impl Position {
+    /// Create a position.
+    pub const fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+}
+
+/// Offsets of the 4 orthogonal neighbours (up, right, down, left).
+#[allow(dead_code)]
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Offsets of the 8 surrounding neighbours (clockwise from the top-left one).
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// A rectangular grid of characters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    /// Cells, row after row.
+    cells: Vec<char>,
+    /// Amount of columns.
+    width: usize,
+    /// Amount of rows.
+    height: usize,
+}
+
+impl Grid {
+    /// Amount of columns.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Amount of rows.
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Check whether a position is inside the grid.
+    pub const fn contains(&self, position: Position) -> bool {
+        position.row < self.height && position.column < self.width
+    }
+
+    /// Get a mutable reference to the cell at a position.
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut char> {
+        if self.contains(position) {
+            Some(&mut self.cells[position.row * self.width + position.column])
+        } else {
+            None
+        }
+    }
+
+    /// Move a position by an offset, if the result is still inside the grid.
+    pub fn offset(&self, position: Position, rows: isize, columns: isize) -> Option<Position> {
+        let row = position.row.checked_add_signed(rows)?;
+        let column = position.column.checked_add_signed(columns)?;
+        let position = Position::new(row, column);
+        self.contains(position).then_some(position)
+    }
+
+    /// Iterate over the 4 orthogonal neighbours of a position.
+    #[allow(dead_code)]
+    pub fn neighbours_4(&self, position: Position) -> impl '_ + Iterator<Item = Position> {
+        NEIGHBOURS_4
+            .into_iter()
+            .filter_map(move |(rows, columns)| self.offset(position, rows, columns))
+    }
+
+    /// Iterate over the 8 surrounding neighbours of a position.
+    pub fn neighbours_8(&self, position: Position) -> impl '_ + Iterator<Item = Position> {
+        NEIGHBOURS_8
+            .into_iter()
+            .filter_map(move |(rows, columns)| self.offset(position, rows, columns))
+    }
+
+    /// Get a row.
+    pub fn row(&self, row: usize) -> Option<&[char]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// Iterate over the rows.
+    pub fn rows(&self) -> impl '_ + Iterator<Item = &[char]> {
+        self.cells.chunks_exact(self.width)
+    }
+
+    /// Iterate over the cells of a column.
+    pub fn column(&self, column: usize) -> impl '_ + Iterator<Item = char> {
+        self.cells
+            .iter()
+            .skip(column)
+            .step_by(self.width)
+            .take(if column < self.width { self.height } else { 0 })
+            .copied()
+    }
+
+    /// Iterate over the columns.
+    pub fn columns(&self) -> impl '_ + Iterator<Item = impl '_ + Iterator<Item = char>> {
+        (0..self.width).map(|column| self.column(column))
+    }
+
+    /// Iterate over all the positions, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height)
+            .flat_map(move |row| (0..width).map(move |column| Position::new(row, column)))
+    }
+
+    /// Iterate over all the cells along with their positions, row after row.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (Position, char)> {
+        self.positions().zip(self.cells.iter().copied())
+    }
+}
+
+impl Index<Position> for Grid {
+    type Output = char;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        assert!(self.contains(position), "Position out of the grid");
+        &self.cells[position.row * self.width + position.column]
+    }
+}
+
+impl IndexMut<Position> for Grid {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        self.get_mut(position).expect("Position out of the grid")
+    }
+}
+
+/// Parse a grid, one row per line.
+impl FromStr for Grid {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (row, line) in s.trim_end_matches('\n').lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars());
+            // Every row must have the width of the first one.
+            let len = cells.len() - before;
+            if *width.get_or_insert(len) != len {
+                return Err(InvalidInput::Ragged { row });
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                cells,
+                width,
+                height,
+            }),
+            _ => Err(InvalidInput::Empty),
+        }
+    }
+}
+
+/// Print the grid, one row per line.
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, InvalidInput, Position};
+
+    const INPUT: &str = "abc
+def";
+
+    #[test]
+    fn parse() {
+        let grid: Grid = INPUT.parse().unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Position::new(1, 2)], 'f');
+        assert!(!grid.contains(Position::new(2, 0)));
+        assert!(!grid.contains(Position::new(0, 3)));
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+        assert_eq!("".parse::<Grid>(), Err(InvalidInput::Empty));
+        assert_eq!(
+            "ab\nc".parse::<Grid>(),
+            Err(InvalidInput::Ragged { row: 1 })
+        );
+        // Spaces are cells.
+        let grid: Grid = "  #\n..#\n".parse().unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.to_string(), "  #\n..#\n");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid: Grid = INPUT.parse().unwrap();
+        let corner = Position::new(0, 0);
+        assert_eq!(
+            grid.neighbours_4(corner).collect::<Vec<_>>(),
+            [Position::new(0, 1), Position::new(1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours_8(corner).collect::<Vec<_>>(),
+            [
+                Position::new(0, 1),
+                Position::new(1, 1),
+                Position::new(1, 0)
+            ]
+        );
+        assert_eq!(grid.neighbours_8(Position::new(1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn iterate() {
+        let grid: Grid = INPUT.parse().unwrap();
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(
+            grid.columns()
+                .map(Iterator::collect)
+                .collect::<Vec<String>>(),
+            ["ad", "be", "cf"]
+        );
+        assert_eq!(grid.iter().nth(4), Some((Position::new(1, 1), 'e')));
+    }
+}
@@ -0,0 +1,436 @@
+use std::{fmt, num::ParseIntError, ops::RangeInclusive, str::FromStr};
+
+/// An unsigned integer type for interval bounds.
+pub trait Value: Copy + Ord + fmt::Debug + FromStr<Err = ParseIntError> {
+    /// The value 0.
+    const ZERO: Self;
+    /// The value 1.
+    const ONE: Self;
+    /// The largest value.
+    const MAX: Self;
+
+    /// Add, returning `None` on overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Subtract, returning `None` on overflow.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+}
+
+/// Implement `Value` for primitive unsigned integers.
+macro_rules! impl_value {
+    ($($t:ty),*) => {
+        $(
+            impl Value for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_value!(u32, u64, u128);
+
+/// A computation does not fit in the value type.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+/// A set of values, stored as sorted, disjoint and non-adjacent ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T>(Vec<RangeInclusive<T>>);
+
+impl<T: Value> IntervalSet<T> {
+    /// The empty set.
+    pub const fn empty() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Iterate over the ranges, in ascending order.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = &RangeInclusive<T>> {
+        self.0.iter()
+    }
+
+    /// Get the smallest value of the set.
+    pub fn min(&self) -> Option<T> {
+        self.0.first().map(|range| *range.start())
+    }
+
+    /// Get the values which do not belong to the set.
+    pub fn complement(&self) -> Self {
+        let mut ranges = Vec::with_capacity(self.0.len() + 1);
+        let mut next = Some(T::ZERO);
+        for range in &self.0 {
+            if let Some(start) = next.filter(|start| start < range.start()) {
+                // The range does not start at 0, so it can be decremented.
+                ranges.push(start..=range.start().checked_sub(T::ONE).unwrap_or(T::ZERO));
+            }
+            next = range.end().checked_add(T::ONE);
+        }
+        ranges.extend(next.map(|start| start..=T::MAX));
+        Self(ranges)
+    }
+
+    /// Get the values belonging to any of the sets.
+    pub fn union(&self, other: &Self) -> Self {
+        self.0.iter().chain(&other.0).cloned().collect()
+    }
+
+    /// Get the values belonging to both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (self.0.iter().peekable(), other.0.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let start = *x.start().max(y.start());
+            let end = *x.end().min(y.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            // Drop the range ending first.
+            if x.end() < y.end() {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self(ranges)
+    }
+
+    /// Get the values belonging to this set but not to the other one.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement())
+    }
+}
+
+/// Build a set from a single range.
+impl<T: Value> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        if range.is_empty() {
+            Self::empty()
+        } else {
+            Self(vec![range])
+        }
+    }
+}
+
+/// Build a set from any ranges, merging the overlapping and adjacent ones.
+impl<T: Value> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut ranges: Vec<_> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        ranges.sort_unstable_by_key(|range| *range.start());
+
+        let mut merged: Vec<RangeInclusive<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                // Overlapping or adjacent to the previous range.
+                Some(last)
+                    if last
+                        .end()
+                        .checked_add(T::ONE)
+                        .is_none_or(|next| *range.start() <= next) =>
+                {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+        Self(merged)
+    }
+}
+
+/// A range of sources shifted to a destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece<T> {
+    /// Source range.
+    pub source: RangeInclusive<T>,
+    /// Destination of the start of the source range.
+    pub destination: T,
+}
+
+impl<T: Value> Piece<T> {
+    /// Translate a value of the source range.
+    fn translate(&self, value: T) -> Result<T, Overflow> {
+        value
+            .checked_sub(*self.source.start())
+            .and_then(|offset| self.destination.checked_add(offset))
+            .ok_or(Overflow)
+    }
+
+    /// Find the value of the source range translated into a value.
+    fn untranslate(&self, value: T) -> Result<T, Overflow> {
+        value
+            .checked_sub(self.destination)
+            .and_then(|offset| self.source.start().checked_add(offset))
+            .ok_or(Overflow)
+    }
+
+    /// Translate a range included in the source range.
+    fn translate_range(&self, range: &RangeInclusive<T>) -> Result<RangeInclusive<T>, Overflow> {
+        Ok(self.translate(*range.start())?..=self.translate(*range.end())?)
+    }
+}
+
+/// A map shifting ranges of values, leaving the other values unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap<T> {
+    /// The pieces, sorted by source and without overlap.
+    pieces: Vec<Piece<T>>,
+}
+
+impl<T: Value> PiecewiseMap<T> {
+    /// Build a map from pieces.
+    /// When the sources of several pieces overlap, the first piece wins.
+    pub fn new(pieces: impl IntoIterator<Item = Piece<T>>) -> Result<Self, Overflow> {
+        let mut covered = IntervalSet::empty();
+        let mut result = Vec::new();
+
+        for piece in pieces {
+            // The whole destination range must fit.
+            piece.translate(*piece.source.end())?;
+            // Keep what is not covered by the previous pieces.
+            let source = IntervalSet::from(piece.source.clone());
+            for range in source.difference(&covered).iter() {
+                result.push(Piece {
+                    source: range.clone(),
+                    destination: piece.translate(*range.start())?,
+                });
+            }
+            covered = covered.union(&source);
+        }
+
+        result.sort_unstable_by_key(|piece| *piece.source.start());
+        Ok(Self { pieces: result })
+    }
+
+    /// Split the whole value range into pieces, including the unchanged
+    ///   ranges between the pieces of the map.
+    pub fn segments(&self) -> Vec<Piece<T>> {
+        let mut segments = Vec::with_capacity(self.pieces.len() * 2 + 1);
+        let mut next = Some(T::ZERO);
+        for piece in &self.pieces {
+            if let Some(start) = next.filter(|start| start < piece.source.start()) {
+                let end = piece.source.start().checked_sub(T::ONE).unwrap_or(T::ZERO);
+                segments.push(Piece {
+                    source: start..=end,
+                    destination: start,
+                });
+            }
+            segments.push(piece.clone());
+            next = piece.source.end().checked_add(T::ONE);
+        }
+        segments.extend(next.map(|start| Piece {
+            source: start..=T::MAX,
+            destination: start,
+        }));
+        segments
+    }
+
+    /// Compose two maps: the result maps a value through this map, then
+    ///   through the next one.
+    pub fn then(&self, next: &Self) -> Result<Self, Overflow> {
+        let next_segments = next.segments();
+        let mut pieces: Vec<Piece<T>> = Vec::new();
+
+        for segment in self.segments() {
+            // Split the image of the segment along the segments of the next map.
+            let image = IntervalSet::from(segment.translate_range(&segment.source)?);
+            for next_segment in &next_segments {
+                let next_source = IntervalSet::from(next_segment.source.clone());
+                for range in image.intersection(&next_source).iter() {
+                    // Find the sources of this part of the image.
+                    let offset = range
+                        .start()
+                        .checked_sub(segment.destination)
+                        .ok_or(Overflow)?;
+                    let start = segment.source.start().checked_add(offset).ok_or(Overflow)?;
+                    let end = range
+                        .end()
+                        .checked_sub(*range.start())
+                        .and_then(|len| start.checked_add(len))
+                        .ok_or(Overflow)?;
+                    let destination = next_segment.translate(*range.start())?;
+                    // Drop the unchanged ranges.
+                    if destination == start {
+                        continue;
+                    }
+                    // Extend the previous piece if it shifts by the same amount.
+                    match pieces.last_mut() {
+                        Some(last)
+                            if last.source.end().checked_add(T::ONE) == Some(start)
+                                && last.translate(*last.source.end())?.checked_add(T::ONE)
+                                    == Some(destination) =>
+                        {
+                            last.source = *last.source.start()..=end;
+                        }
+                        _ => pieces.push(Piece {
+                            source: start..=end,
+                            destination,
+                        }),
+                    }
+                }
+            }
+        }
+
+        Ok(Self { pieces })
+    }
+
+    /// Map a value.
+    pub fn map(&self, value: T) -> Result<T, Overflow> {
+        // Find the last piece starting before the value.
+        let index = self
+            .pieces
+            .partition_point(|piece| *piece.source.start() <= value);
+        match index.checked_sub(1).map(|index| &self.pieces[index]) {
+            Some(piece) if value <= *piece.source.end() => piece.translate(value),
+            _ => Ok(value),
+        }
+    }
+
+    /// Map a set of values.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> Result<IntervalSet<T>, Overflow> {
+        let mut ranges = Vec::new();
+        for segment in self.segments() {
+            let source = IntervalSet::from(segment.source.clone());
+            for range in source.intersection(set).iter() {
+                ranges.push(segment.translate_range(range)?);
+            }
+        }
+        Ok(ranges.into_iter().collect())
+    }
+
+    /// Find the values mapped into a set of values.
+    pub fn preimage(&self, set: &IntervalSet<T>) -> Result<IntervalSet<T>, Overflow> {
+        let mut ranges = Vec::new();
+        for segment in self.segments() {
+            let image = IntervalSet::from(segment.translate_range(&segment.source)?);
+            for range in image.intersection(set).iter() {
+                ranges.push(
+                    segment.untranslate(*range.start())?..=segment.untranslate(*range.end())?,
+                );
+            }
+        }
+        Ok(ranges.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntervalSet, Overflow, Piece, PiecewiseMap, Value};
+
+    impl<T: Value> IntervalSet<T> {
+        /// The set of every value.
+        pub fn full() -> Self {
+            Self(vec![T::ZERO..=T::MAX])
+        }
+
+        /// Check whether a value belongs to the set.
+        pub fn contains(&self, value: T) -> bool {
+            // Find the last range starting before the value.
+            let index = self.0.partition_point(|range| *range.start() <= value);
+            index > 0 && value <= *self.0[index - 1].end()
+        }
+    }
+
+    #[test]
+    fn normalize() {
+        let set: IntervalSet<u32> = [8..=9, 1..=2, 3..=4, 2..=3, 6..=6].into_iter().collect();
+        assert_eq!(set, IntervalSet(vec![1..=4, 6..=6, 8..=9]));
+        assert!(set.contains(6));
+        assert!(!set.contains(5));
+        assert_eq!(set.min(), Some(1));
+
+        // Merging up to the largest value must not overflow.
+        let set: IntervalSet<u32> = [0..=u32::MAX, 5..=6].into_iter().collect();
+        assert_eq!(set, IntervalSet::full());
+    }
+
+    #[test]
+    fn operations() {
+        let a: IntervalSet<u32> = [0..=9, 20..=29].into_iter().collect();
+        let b = IntervalSet::from(5..=24);
+        assert_eq!(a.union(&b), IntervalSet(vec![0..=29]));
+        assert_eq!(a.intersection(&b), IntervalSet(vec![5..=9, 20..=24]));
+        assert_eq!(a.difference(&b), IntervalSet(vec![0..=4, 25..=29]));
+        assert_eq!(b.difference(&a), IntervalSet(vec![10..=19]));
+        assert_eq!(a.complement(), IntervalSet(vec![10..=19, 30..=u32::MAX]));
+        assert_eq!(
+            IntervalSet::<u32>::full().complement(),
+            IntervalSet::empty()
+        );
+    }
+
+    #[test]
+    fn piecewise_map() {
+        let map = PiecewiseMap::new([
+            Piece {
+                source: 98..=99,
+                destination: 50,
+            },
+            Piece {
+                source: 50..=97,
+                destination: 52,
+            },
+            // Shadowed by the first piece, except for 100.
+            Piece {
+                source: 99..=100,
+                destination: 0,
+            },
+        ])
+        .unwrap();
+        assert_eq!(map.map(79), Ok(81));
+        assert_eq!(map.map(99), Ok(51));
+        assert_eq!(map.map(100), Ok(1));
+        assert_eq!(map.map(10), Ok(10));
+        assert_eq!(map.segments().len(), 5);
+
+        let seeds: IntervalSet<u32> = [79..=92, 55..=67].into_iter().collect();
+        assert_eq!(map.map_set(&seeds), Ok(IntervalSet(vec![57..=69, 81..=94])));
+        // Adjacent results are merged.
+        let all = IntervalSet::from(0..=200);
+        assert_eq!(map.map_set(&all), Ok(IntervalSet(vec![0..=99, 101..=200])));
+
+        // Several values may be mapped into the same one.
+        assert_eq!(
+            map.preimage(&IntervalSet::from(1..=1)),
+            Ok([1..=1, 100..=100].into_iter().collect())
+        );
+        assert_eq!(
+            map.preimage(&IntervalSet::from(51..=52)),
+            Ok(IntervalSet::from(50..=50).union(&IntervalSet::from(99..=99)))
+        );
+        assert_eq!(map.preimage(&IntervalSet::full()), Ok(IntervalSet::full()));
+
+        // The composition maps through both maps.
+        let next = PiecewiseMap::new([Piece {
+            source: 40..=59,
+            destination: 100,
+        }])
+        .unwrap();
+        let composed = map.then(&next).unwrap();
+        for value in 0..=200 {
+            assert_eq!(composed.map(value), next.map(map.map(value).unwrap()));
+        }
+        // Composing with the identity does not change the map.
+        let identity = PiecewiseMap::new([]).unwrap();
+        assert_eq!(map.then(&identity).unwrap(), map);
+        assert_eq!(identity.then(&map).unwrap(), map);
+
+        // The destination range must fit.
+        assert_eq!(
+            PiecewiseMap::new([Piece {
+                source: 0..=1,
+                destination: u32::MAX,
+            }]),
+            Err(Overflow)
+        );
+    }
+}
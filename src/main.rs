@@ -24,17 +24,31 @@
 #![warn(missing_docs)]
 
 mod day1;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
 mod day2;
 mod day3;
 mod day4;
 mod day5;
 mod day6;
+mod day7;
+mod day8;
+mod day9;
+mod grid;
+mod interval;
+mod polynomial;
 
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{CellAlignment, Table};
+use std::{env, process};
 
-fn main() {
+/// Print the answers of every day.
+fn summary() {
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
@@ -45,8 +59,45 @@ fn main() {
         .add_row([3, day3::first_part(), day3::second_part()])
         .add_row([4, day4::first_part(), day4::second_part()])
         .add_row([5, day5::first_part(), day5::second_part()])
-        .add_row([6, day6::first_part(), day6::second_part()])
+        .add_row([
+            6.to_string(),
+            day6::first_part().to_string(),
+            day6::second_part().to_string(),
+        ])
+        .add_row([7, day7::first_part(), day7::second_part()])
+        .add_row([8, day8::first_part(), day8::second_part()])
+        .add_row([9, day9::first_part(), day9::second_part()])
+        .add_row([10, day10::first_part(), day10::second_part()])
+        .add_row([11, day11::first_part(), day11::second_part()])
+        .add_row([12, day12::first_part(), day12::second_part()])
+        .add_row([13, day13::first_part(), day13::second_part()])
+        .add_row([14, day14::first_part(), day14::second_part()])
+        .add_row([15, day15::first_part(), day15::second_part()])
         .column_iter_mut()
         .for_each(|col| col.set_cell_alignment(CellAlignment::Right));
     println!("{table}");
 }
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        None => {
+            summary();
+            Ok(())
+        }
+        Some("day3") => day3::command(args),
+        Some("day4") => day4::command(args),
+        Some("day5") => day5::command(args),
+        Some("day8") => day8::command(args),
+        Some("day10") => day10::command(args),
+        Some("day11") => day11::command(args),
+        Some("day12") => day12::command(args),
+        Some("day14") => day14::command(args),
+        Some("day15") => day15::command(args),
+        Some(command) => Err(format!("Unknown command: {command}")),
+    };
+    if let Err(error) = result {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
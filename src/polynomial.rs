@@ -0,0 +1,91 @@
+/// A polynomial sequence, known by the values it takes at consecutive
+///   positions from 0.
+///
+/// It is stored in Newton's forward form: the first value of the sequence,
+///   then the first value of its differences, of their differences, etc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial(Vec<i128>);
+
+impl Polynomial {
+    /// Fit the polynomial of lowest degree going through some values, taking
+    ///   the differences until they are all zero.
+    ///
+    /// Returns none on overflow.
+    pub fn fit(values: &[i64]) -> Option<Self> {
+        let mut differences: Vec<i128> = values.iter().copied().map(i128::from).collect();
+        let mut coefficients = Vec::new();
+        while differences.iter().any(|difference| *difference != 0) {
+            coefficients.push(differences[0]);
+            differences = differences
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<_>>()?;
+        }
+        Some(Self(coefficients))
+    }
+
+    /// Evaluate at a position, before, inside or after the fitted values.
+    ///
+    /// Returns none on overflow.
+    pub fn at(&self, position: i64) -> Option<i64> {
+        let position = i128::from(position);
+        // Sum the coefficients times the binomials (position choose k),
+        //   updated with (position choose k) = (position choose k - 1)
+        //   * (position - k + 1) / k, which is always exact.
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+        for (k, coefficient) in (0..).zip(&self.0) {
+            if k > 0 {
+                binomial = binomial.checked_mul(position - k + 1)? / k;
+            }
+            value = value.checked_add(coefficient.checked_mul(binomial)?)?;
+        }
+        i64::try_from(value).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Polynomial;
+
+    impl Polynomial {
+        /// Get the degree, none for the null polynomial.
+        const fn degree(&self) -> Option<usize> {
+            self.0.len().checked_sub(1)
+        }
+    }
+
+    #[test]
+    fn fit() {
+        assert_eq!(Polynomial::fit(&[]).unwrap().degree(), None);
+        assert_eq!(Polynomial::fit(&[0, 0, 0]).unwrap().degree(), None);
+        assert_eq!(Polynomial::fit(&[5, 5, 5]).unwrap().degree(), Some(0));
+        assert_eq!(Polynomial::fit(&[0, 3, 6, 9]).unwrap().degree(), Some(1));
+        assert_eq!(
+            Polynomial::fit(&[1, 3, 6, 10, 15]).unwrap().degree(),
+            Some(2)
+        );
+        assert_eq!(
+            Polynomial::fit(&[0, 1, 8, 27, 64]).unwrap().degree(),
+            Some(3)
+        );
+        // Not enough values to find zero differences.
+        assert_eq!(Polynomial::fit(&[1, 2, 4]).unwrap().degree(), Some(2));
+        // The differences of alternating extremes grow too fast.
+        let values: Vec<_> = (0..80)
+            .map(|index| if index % 2 == 0 { i64::MAX } else { i64::MIN })
+            .collect();
+        assert_eq!(Polynomial::fit(&values), None);
+    }
+
+    #[test]
+    fn at() {
+        let cubes = Polynomial::fit(&[0, 1, 8, 27, 64]).unwrap();
+        assert_eq!(cubes.at(5), Some(125));
+        assert_eq!(cubes.at(-3), Some(-27));
+        assert_eq!(cubes.at(1_000), Some(1_000_000_000));
+        assert_eq!(cubes.at(3_000_000), None);
+        assert_eq!(Polynomial::fit(&[]).unwrap().at(7), Some(0));
+        assert_eq!(Polynomial::fit(&[-4, -1, 2]).unwrap().at(-1), Some(-7));
+    }
+}